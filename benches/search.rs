@@ -0,0 +1,105 @@
+//! Compare sequential and parallel State searches on a valley full of blizzards like day 24's,
+//! run with cargo bench --features parallel
+
+use jungle::{Limits, State};
+use std::time::{Duration, Instant};
+
+const WIDTH: i32 = 80;
+const HEIGHT: i32 = 30;
+const BLIZZARDS: usize = 600;
+const RUNS: usize = 3;
+
+struct Valley {
+    // Each blizzard has a start position and a direction
+    blizzards: Vec<(i32, i32, i32, i32)>,
+}
+
+impl Valley {
+    fn new() -> Self {
+        // Simple linear congruential generator so that every run is the same
+        let mut seed: u64 = 2022;
+        let mut random = |n: i32| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % n as u64) as i32
+        };
+        let mut blizzards = Vec::with_capacity(BLIZZARDS);
+        for _ in 0..BLIZZARDS {
+            let (dx, dy) = [(1, 0), (-1, 0), (0, 1), (0, -1)][random(4) as usize];
+            blizzards.push((random(WIDTH), random(HEIGHT), dx, dy));
+        }
+        Valley { blizzards }
+    }
+
+    fn clear(&self, x: i32, y: i32, time: i32) -> bool {
+        self.blizzards.iter().all(|&(bx, by, dx, dy)| {
+            (bx + dx * time).rem_euclid(WIDTH) != x || (by + dy * time).rem_euclid(HEIGHT) != y
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+struct Expedition {
+    x: i32,
+    y: i32,
+    time: i32,
+}
+
+impl State<Valley> for Expedition {
+    fn describe(&self, _valley: &Valley) -> String {
+        format!("({}, {}) at {}", self.x, self.y, self.time)
+    }
+
+    fn next(&self, valley: &Valley) -> Vec<Self> {
+        let time = self.time + 1;
+        let moves = [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)];
+        let mut v = Vec::new();
+        for (dx, dy) in moves {
+            let (x, y) = (self.x + dx, self.y + dy);
+            if (0..WIDTH).contains(&x) && (0..HEIGHT).contains(&y) && valley.clear(x, y, time) {
+                v.push(Expedition { x, y, time });
+            }
+        }
+        v
+    }
+}
+
+fn fastest<F: Fn() -> usize>(f: F) -> (usize, Duration) {
+    let mut best = Duration::MAX;
+    let mut result = 0;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        result = f();
+        best = best.min(start.elapsed());
+    }
+    (result, best)
+}
+
+fn main() {
+    let valley = Valley::new();
+    let start = Expedition {
+        x: 0,
+        y: 0,
+        time: 0,
+    };
+    let exit = |e: &Expedition| e.x == WIDTH - 1 && e.y == HEIGHT - 1;
+    let limits = Limits::default();
+
+    let (sequential, t1) = fastest(|| {
+        Expedition::bounded_steps(start, exit, &valley, &limits).expect("The exit is reachable")
+    });
+    let (parallel, t2) = fastest(|| {
+        Expedition::par_bounded_steps(start, exit, &valley, &limits).expect("The exit is reachable")
+    });
+    assert_eq!(sequential, parallel);
+    println!("steps: {sequential}, sequential {t1:?}, parallel {t2:?}");
+
+    let (sequential, t1) = fastest(|| Expedition::count(start, 60, &valley));
+    let (parallel, t2) = fastest(|| Expedition::par_count(start, 60, &valley));
+    assert_eq!(sequential, parallel);
+    println!("count: {sequential} states, sequential {t1:?}, parallel {t2:?}");
+
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    println!("{threads} threads available");
+}
@@ -0,0 +1,254 @@
+use crate::{Answer, Contents, ParseError, Part};
+use std::collections::HashMap;
+
+/// Expected answers for each input set, read from a small subset of TOML with a table for each
+/// input set and a key for each day and part, e.g.
+///
+/// ```toml
+/// [example]
+/// 1a = 24000
+/// 5a = "CMZ"
+/// ```
+///
+/// Values may be integers, "basic" or 'literal' strings, or multi-line """ or ''' strings, and
+/// pictures are best written as multi-line literal strings
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    sets: HashMap<String, HashMap<(u8, Part), Answer>>,
+}
+
+fn key(ctxt: &Contents, s: &str) -> Result<(u8, Part), ParseError> {
+    let Some(k) = s.len().checked_sub(1).filter(|&k| s.is_char_boundary(k)) else {
+        return Err(ctxt.error(s, "key should be a day and part, e.g. 4b"));
+    };
+    let (day, part) = s.split_at(k);
+    match (day.parse(), part.parse()) {
+        (Ok(day), Ok(part)) => Ok((day, part)),
+        _ => Err(ctxt.error(s, "key should be a day and part, e.g. 4b")),
+    }
+}
+
+fn unquote<'t>(ctxt: &Contents, s: &'t str) -> Result<&'t str, ParseError> {
+    for q in ['"', '\''] {
+        if let Some(inner) = s.strip_prefix(q) {
+            return inner
+                .strip_suffix(q)
+                .ok_or_else(|| ctxt.error(s, "unterminated quoted name"));
+        }
+    }
+    Ok(s)
+}
+
+/// Interpret escape sequences in a basic string
+fn escaped(ctxt: &Contents, s: &str) -> Result<String, ParseError> {
+    let mut out = String::new();
+    let mut chars = s.char_indices();
+    while let Some((k, ch)) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        let bad = || ctxt.error(&s[k..], "unknown escape sequence");
+        match chars.next().map(|(_, ch)| ch) {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('r') => out.push('\r'),
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).map(|(_, ch)| ch).collect();
+                let ch = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(bad)?;
+                out.push(ch);
+            }
+            _ => return Err(bad()),
+        }
+    }
+    Ok(out)
+}
+
+impl Answers {
+    pub fn parse(ctxt: &Contents) -> Result<Self, ParseError> {
+        let mut answers = Answers::default();
+        let mut set: Option<String> = None;
+        let mut lines = ctxt.lines();
+
+        while let Some(line) = lines.next() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = trimmed.strip_prefix('[') {
+                let name = name
+                    .strip_suffix(']')
+                    .ok_or_else(|| ctxt.error(trimmed, "table header should end with ]"))?;
+                set = Some(unquote(ctxt, name.trim())?.to_string());
+                continue;
+            }
+
+            let (k, value) = trimmed
+                .split_once('=')
+                .ok_or_else(|| ctxt.error(trimmed, "expected key = value"))?;
+            let (day, part) = key(ctxt, unquote(ctxt, k.trim())?)?;
+            let value = value.trim();
+
+            let answer = if let Some(rest) = value
+                .strip_prefix("\"\"\"")
+                .or_else(|| value.strip_prefix("'''"))
+            {
+                let delimiter = &value[..3];
+                // A newline immediately after the opening delimiter is not part of the string
+                let mut text = String::new();
+                let mut first = Some(rest);
+                loop {
+                    let part = match first.take() {
+                        Some("") => lines
+                            .next()
+                            .ok_or_else(|| ctxt.error(value, "unterminated multi-line string"))?,
+                        Some(part) => part,
+                        None => {
+                            text.push('\n');
+                            lines.next().ok_or_else(|| {
+                                ctxt.error(value, "unterminated multi-line string")
+                            })?
+                        }
+                    };
+                    if let Some(end) = part.find(delimiter) {
+                        text.push_str(&part[..end]);
+                        let trailing = part[end + 3..].trim();
+                        if !trailing.is_empty() && !trailing.starts_with('#') {
+                            return Err(ctxt.error(trailing, "unexpected text after string"));
+                        }
+                        break;
+                    }
+                    text.push_str(part);
+                }
+                if delimiter == "\"\"\"" {
+                    text = escaped(ctxt, &text)?;
+                }
+                Answer::Text(text)
+            } else if let Some(rest) = value.strip_prefix('"') {
+                let end = rest
+                    .char_indices()
+                    .scan(false, |escape, (k, ch)| {
+                        let done = ch == '"' && !*escape;
+                        *escape = ch == '\\' && !*escape;
+                        Some((k, done))
+                    })
+                    .find(|&(_, done)| done)
+                    .map(|(k, _)| k)
+                    .ok_or_else(|| ctxt.error(value, "unterminated string"))?;
+                Answer::Text(escaped(ctxt, &rest[..end])?)
+            } else if let Some(rest) = value.strip_prefix('\'') {
+                let end = rest
+                    .find('\'')
+                    .ok_or_else(|| ctxt.error(value, "unterminated string"))?;
+                Answer::Text(rest[..end].to_string())
+            } else {
+                let number = value.split('#').next().unwrap_or_default().trim();
+                let n: i64 = number
+                    .replace('_', "")
+                    .parse()
+                    .map_err(|e| ctxt.error(number, e))?;
+                Answer::Number(n)
+            };
+
+            let Some(set) = &set else {
+                return Err(ctxt.error(trimmed, "answers should be inside a [set] table"));
+            };
+            answers
+                .sets
+                .entry(set.clone())
+                .or_default()
+                .insert((day, part), answer);
+        }
+        Ok(answers)
+    }
+
+    pub fn get(&self, set: &str, day: u8, part: Part) -> Option<&Answer> {
+        self.sets.get(set)?.get(&(day, part))
+    }
+}
+
+/// Whether the answer is what was expected, text is compared ignoring whitespace at the end of
+/// each line, so pictures needn't preserve trailing spaces
+pub fn matches(expected: &Answer, actual: &Answer) -> bool {
+    fn normal(s: &str) -> Vec<&str> {
+        let mut v: Vec<&str> = s.lines().map(str::trim_end).collect();
+        while v.last() == Some(&"") {
+            v.pop();
+        }
+        v
+    }
+
+    match (expected, actual) {
+        (Answer::Number(e), Answer::Number(a)) => e == a,
+        (e, a) => normal(&e.to_string()) == normal(&a.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answers::{matches, Answers};
+    use crate::{Answer, Contents, Part};
+
+    const MANIFEST: &str = r#"# Expected answers
+[example]
+1a = 24000
+01b = 45_000 # top three
+5a = "CMZ"
+'25a' = '2=-1=0'
+10b = '''
+#.#
+.#.
+'''
+
+["my input"]
+6b = """tab\tand "quote" é"""
+"#;
+
+    fn contents(text: &str) -> Contents {
+        Contents {
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let answers = Answers::parse(&contents(MANIFEST)).unwrap();
+        let get = |set, day, part| answers.get(set, day, part).cloned();
+        assert_eq!(get("example", 1, Part::A), Some(Answer::Number(24000)));
+        assert_eq!(get("example", 1, Part::B), Some(Answer::Number(45000)));
+        assert_eq!(get("example", 5, Part::A), Some("CMZ".into()));
+        assert_eq!(get("example", 25, Part::A), Some("2=-1=0".into()));
+        assert_eq!(get("example", 10, Part::B), Some("#.#\n.#.\n".into()));
+        assert_eq!(
+            get("my input", 6, Part::B),
+            Some("tab\tand \"quote\" é".into())
+        );
+        assert_eq!(get("real", 1, Part::A), None);
+    }
+
+    #[test]
+    fn errors() {
+        let e = Answers::parse(&contents("[real]\n4c = 7\n")).unwrap_err();
+        assert_eq!((e.line, e.column, e.text.as_str()), (2, 1, "4c"));
+        let e = Answers::parse(&contents("[real]\n4a = 7x\n")).unwrap_err();
+        assert_eq!((e.line, e.column), (2, 6));
+        let e = Answers::parse(&contents("4a = 7\n")).unwrap_err();
+        assert_eq!(e.line, 1);
+        let e = Answers::parse(&contents("[real]\n4a = '''\nabc\n")).unwrap_err();
+        assert_eq!(e.line, 2);
+    }
+
+    #[test]
+    fn picture() {
+        let picture = Answer::Picture("#  \n # \n".to_string());
+        assert!(matches(&"#\n #".into(), &picture));
+        assert!(!matches(&"#\n#".into(), &picture));
+        assert!(matches(&Answer::Number(12), &"12".into()));
+        assert!(!matches(&Answer::Number(12), &Answer::Number(21)));
+    }
+}
@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign};
+use std::str::FromStr;
+
+/// Arbitrary precision non-negative integer, for exact counts too large for a usize
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Big {
+    // Least significant first, never with trailing zeroes, so zero is empty
+    limbs: Vec<u32>,
+}
+
+impl Big {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::from(1u32)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+
+    /// Divide in place by a small divisor, returning the remainder
+    pub fn div_small(&mut self, divisor: u32) -> u32 {
+        assert!(divisor != 0, "Division by zero");
+        let mut remainder: u64 = 0;
+        for limb in self.limbs.iter_mut().rev() {
+            let n = (remainder << 32) | *limb as u64;
+            *limb = (n / divisor as u64) as u32;
+            remainder = n % divisor as u64;
+        }
+        self.trim();
+        remainder as u32
+    }
+
+    /// The value as a usize, if it fits
+    pub fn to_usize(&self) -> Option<usize> {
+        if self.limbs.len() > 2 {
+            return None;
+        }
+        let mut n: u64 = 0;
+        for &limb in self.limbs.iter().rev() {
+            n = (n << 32) | limb as u64;
+        }
+        n.try_into().ok()
+    }
+}
+
+impl From<u32> for Big {
+    fn from(n: u32) -> Self {
+        Self::from(n as u64)
+    }
+}
+
+impl From<u64> for Big {
+    fn from(n: u64) -> Self {
+        let mut big = Big {
+            limbs: vec![n as u32, (n >> 32) as u32],
+        };
+        big.trim();
+        big
+    }
+}
+
+impl From<usize> for Big {
+    fn from(n: usize) -> Self {
+        Self::from(n as u64)
+    }
+}
+
+impl Ord for Big {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for Big {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl AddAssign<&Big> for Big {
+    fn add_assign(&mut self, other: &Big) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+        let mut carry = 0;
+        for (k, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + *other.limbs.get(k).unwrap_or(&0) as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+}
+
+impl Add<&Big> for Big {
+    type Output = Big;
+
+    fn add(mut self, other: &Big) -> Big {
+        self += other;
+        self
+    }
+}
+
+impl MulAssign<u32> for Big {
+    fn mul_assign(&mut self, n: u32) {
+        let mut carry = 0;
+        for limb in self.limbs.iter_mut() {
+            let product = *limb as u64 * n as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        self.trim();
+    }
+}
+
+impl Mul<&Big> for &Big {
+    type Output = Big;
+
+    fn mul(self, other: &Big) -> Big {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let n = limbs[i + j] as u64 + a as u64 * b as u64 + carry;
+                limbs[i + j] = n as u32;
+                carry = n >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+        let mut big = Big { limbs };
+        big.trim();
+        big
+    }
+}
+
+impl fmt::Display for Big {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const CHUNK: u32 = 1_000_000_000;
+
+        let mut n = self.clone();
+        let mut chunks = Vec::new();
+        while !n.is_zero() {
+            chunks.push(n.div_small(CHUNK));
+        }
+        let mut s = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            s += &format!("{chunk:09}");
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+impl FromStr for Big {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("No digits");
+        }
+        let mut big = Big::zero();
+        for b in s.bytes() {
+            if !b.is_ascii_digit() {
+                return Err("Only decimal digits are allowed");
+            }
+            big *= 10;
+            big += &Big::from((b - b'0') as u32);
+        }
+        Ok(big)
+    }
+}
+
+/// n! exactly
+pub fn factorial(n: u32) -> Big {
+    let mut product = Big::one();
+    for k in 2..=n {
+        product *= k;
+    }
+    product
+}
+
+/// Number of ways to choose k things from n, exactly
+pub fn binomial(n: u32, k: u32) -> Big {
+    if k > n {
+        return Big::zero();
+    }
+    let k = k.min(n - k);
+    let mut result = Big::one();
+    for i in 1..=k {
+        // Each partial result is itself a binomial coefficient, so this division is exact
+        result *= n - k + i;
+        let remainder = result.div_small(i);
+        debug_assert_eq!(remainder, 0);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::big::{binomial, factorial, Big};
+
+    #[test]
+    fn arithmetic() {
+        let a: Big = "340282366920938463463374607431768211456".parse().unwrap();
+        let b = Big::from(u64::MAX) + &Big::one();
+        assert_eq!(&b * &b, a);
+        assert_eq!(a.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(b.to_string(), "18446744073709551616");
+        assert_eq!(Big::zero().to_string(), "0");
+        assert_eq!(format!("{:>5}", Big::from(42u32)), "   42");
+        assert!(a > b && b > Big::from(u64::MAX));
+        assert_eq!(Big::from(12345usize).to_usize(), Some(12345));
+        assert_eq!(a.to_usize(), None);
+        assert!("12a".parse::<Big>().is_err());
+    }
+
+    #[test]
+    fn counts() {
+        assert_eq!(factorial(0), Big::one());
+        assert_eq!(
+            factorial(30).to_string(),
+            "265252859812191058636308480000000"
+        );
+        assert_eq!(
+            binomial(100, 50).to_string(),
+            "100891344545564193334812497256"
+        );
+        assert_eq!(binomial(5, 7), Big::zero());
+        for n in 0..=20 {
+            assert_eq!(factorial(n).to_usize(), crate::factorial(n as usize));
+        }
+        for (n, k) in [(10, 3), (52, 5), (60, 30), (66, 33)] {
+            assert_eq!(
+                binomial(n, k).to_usize(),
+                crate::binomial(n as usize, k as usize)
+            );
+        }
+    }
+}
@@ -1,7 +1,6 @@
-use jungle::readfile;
-use jungle::Contents;
+use jungle::{Answer, Contents};
 
-fn calories(input: Contents) -> Vec<u64> {
+fn calories(input: &Contents) -> Vec<u64> {
     let mut elves = Vec::new();
     let mut total: u64 = 0;
     for calorie in input.lines() {
@@ -12,21 +11,55 @@ fn calories(input: Contents) -> Vec<u64> {
             total = 0;
         }
     }
+    // The last elf has no blank line after them
+    elves.push(total);
     elves
 }
 
-pub fn a() {
-    let ctxt = readfile("01");
+pub fn a(ctxt: &Contents) -> Answer {
     let mut elves = calories(ctxt);
     let (_, most, _) = elves.select_nth_unstable_by(0, |a, b| b.cmp(a));
-    println!("Elf with most calories has: {most}");
+    (*most).into()
 }
 
-pub fn b() {
-    let ctxt = readfile("01");
+pub fn b(ctxt: &Contents) -> Answer {
     let mut elves = calories(ctxt);
     elves.select_nth_unstable_by(2, |a, b| b.cmp(a));
     let top = &elves[0..3];
     let total: u64 = top.iter().sum();
-    println!("Top three elves have {total}");
+    total.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day01::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(24000));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(45000));
+    }
 }
@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 fn mistake(s: &str) -> u32 {
     match s {
@@ -34,14 +34,35 @@ fn correct(s: &str) -> u32 {
     }
 }
 
-pub fn a() {
-    let ctxt = readfile("02");
+pub fn a(ctxt: &Contents) -> Answer {
     let total: u32 = ctxt.lines().map(mistake).sum();
-    println!("Following the guide according to our mistaken understanding scores: {total}");
+    total.into()
 }
 
-pub fn b() {
-    let ctxt = readfile("02");
+pub fn b(ctxt: &Contents) -> Answer {
     let total: u32 = ctxt.lines().map(correct).sum();
-    println!("Following the guide correctly scores: {total}");
+    total.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day02::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "A Y
+B X
+C Z
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(15));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(12));
+    }
 }
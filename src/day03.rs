@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 fn p(c: char) -> u32 {
     let priority = match c {
@@ -21,10 +21,9 @@ fn priority(s: &str) -> u32 {
     panic!("Somehow this bag's compartments are correct");
 }
 
-pub fn a() {
-    let ctxt = readfile("03");
+pub fn a(ctxt: &Contents) -> Answer {
     let total: u32 = ctxt.lines().map(priority).sum();
-    println!("Sum of priorities is {total}");
+    total.into()
 }
 
 fn badge(s1: &str, s2: &str, s3: &str) -> u32 {
@@ -36,8 +35,7 @@ fn badge(s1: &str, s2: &str, s3: &str) -> u32 {
     panic!("Somehow the elves have no items in common");
 }
 
-pub fn b() {
-    let ctxt = readfile("03");
+pub fn b(ctxt: &Contents) -> Answer {
     let mut lines = ctxt.lines();
     let mut total: u32 = 0;
     while let Some(elf1) = lines.next() {
@@ -45,5 +43,31 @@ pub fn b() {
         let elf3 = lines.next().unwrap();
         total += badge(elf1, elf2, elf3);
     }
-    println!("The total of priorities is {total}");
+    total.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day03::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(157));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(70));
+    }
 }
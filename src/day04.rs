@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 fn assignment(s: &str) -> (u32, u32) {
     let (a, b) = s.split_once('-').unwrap();
@@ -11,8 +11,7 @@ fn contains(a: (u32, u32), b: (u32, u32)) -> bool {
     a.0 <= b.0 && a.1 >= b.1
 }
 
-pub fn a() {
-    let ctxt = readfile("04");
+pub fn a(ctxt: &Contents) -> Answer {
     let mut overlaps = 0;
     for line in ctxt.lines() {
         let (a, b) = line.split_once(',').unwrap();
@@ -22,15 +21,14 @@ pub fn a() {
             overlaps += 1;
         }
     }
-    println!("{overlaps} pairs fully overlapped");
+    overlaps.into()
 }
 
 fn unrelated(a: (u32, u32), b: (u32, u32)) -> bool {
     a.1 < b.0 || a.0 > b.1
 }
 
-pub fn b() {
-    let ctxt = readfile("04");
+pub fn b(ctxt: &Contents) -> Answer {
     let mut overlaps = 0;
     for line in ctxt.lines() {
         let (a, b) = line.split_once(',').unwrap();
@@ -40,5 +38,31 @@ pub fn b() {
             overlaps += 1;
         }
     }
-    println!("{overlaps} pairs overlapped at least partially");
+    overlaps.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day04::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(2));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(4));
+    }
 }
@@ -1,23 +1,23 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
-const STACKS: usize = 9;
-const EMPTY: Vec<u8> = Vec::new();
-
-fn interpret(text: &str) -> ([Vec<u8>; STACKS], Vec<&str>) {
+fn interpret(text: &str) -> (Vec<Vec<u8>>, Vec<&str>) {
     let mut lines = text.lines();
-    let mut stacks = [EMPTY; STACKS];
+    let mut stacks: Vec<Vec<u8>> = Vec::new();
 
     loop {
         let line = lines
             .next()
             .expect("Should be both list of crates and instructions in file");
-        if line.len() != (4 * STACKS) - 1 {
+        if line.is_empty() {
             break;
         }
         let bytes = line.as_bytes();
         for (k, bytes) in bytes.chunks(4).enumerate() {
-            if bytes[0] == b'[' && bytes[2] == b']' {
-                stacks[k].push(bytes[1]);
+            if k == stacks.len() {
+                stacks.push(Vec::new());
+            }
+            if let [b'[', label, b']', ..] = bytes {
+                stacks[k].push(*label);
             }
         }
     }
@@ -48,8 +48,14 @@ fn step(line: &str) -> (usize, usize, usize) {
     (count, from - 1, to - 1)
 }
 
-pub fn a() {
-    let ctxt = readfile("05");
+fn tops(stacks: &[Vec<u8>]) -> String {
+    stacks
+        .iter()
+        .map(|stack| *stack.last().unwrap() as char)
+        .collect()
+}
+
+pub fn a(ctxt: &Contents) -> Answer {
     let (mut stacks, ins) = interpret(&ctxt.text);
     for i in ins {
         let (count, from, to) = step(i);
@@ -60,15 +66,10 @@ pub fn a() {
             stacks[to].push(top);
         }
     }
-    for stack in stacks.iter() {
-        let top = stack.last().unwrap();
-        print!("{}", *top as char);
-    }
-    println!(" is the order of crates on top");
+    tops(&stacks).into()
 }
 
-pub fn b() {
-    let ctxt = readfile("05");
+pub fn b(ctxt: &Contents) -> Answer {
     let (mut stacks, ins) = interpret(&ctxt.text);
     for i in ins {
         let (count, from, to) = step(i);
@@ -84,9 +85,25 @@ pub fn b() {
             stacks[to].push(top);
         }
     }
-    for stack in stacks.iter() {
-        let top = stack.last().unwrap();
-        print!("{}", *top as char);
+    tops(&stacks).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day05::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2\n";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::from("CMZ"));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::from("MCD"));
     }
-    println!(" is the order of crates on top");
 }
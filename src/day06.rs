@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 fn repeats(bytes: &[u8]) -> bool {
     let mut found = [false; 256];
@@ -20,16 +20,35 @@ fn start(bytes: &[u8], count: usize) -> usize {
     panic!("Input data contains no start marker");
 }
 
-pub fn a() {
-    let ctxt = readfile("06");
+pub fn a(ctxt: &Contents) -> Answer {
     let packet = ctxt.value().as_bytes();
     let offset = start(packet, 4);
-    println!("{offset} characters processed before the first start-of-packet marker is detected");
+    offset.into()
 }
 
-pub fn b() {
-    let ctxt = readfile("06");
+pub fn b(ctxt: &Contents) -> Answer {
     let packet = ctxt.value().as_bytes();
     let offset = start(packet, 14);
-    println!("{offset} characters processed before the first start-of-message marker is detected");
+    offset.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day06::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(7));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(19));
+    }
 }
@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 fn log2dirs(lines: &mut dyn Iterator<Item = &str>) -> Vec<usize> {
     let mut v = Vec::new();
@@ -45,20 +45,61 @@ fn log2dirs(lines: &mut dyn Iterator<Item = &str>) -> Vec<usize> {
     v
 }
 
-pub fn a() {
-    let ctxt = readfile("07");
+pub fn a(ctxt: &Contents) -> Answer {
     let dirs = log2dirs(&mut ctxt.lines());
     let sum: usize = dirs.into_iter().filter(|&v| v <= 100_000).sum();
-    println!("{sum}");
+    sum.into()
 }
 
 const SPACE: usize = 70_000_000;
 const NEED: usize = 30_000_000;
 
-pub fn b() {
-    let ctxt = readfile("07");
+pub fn b(ctxt: &Contents) -> Answer {
     let dirs = log2dirs(&mut ctxt.lines());
     let clear = dirs.last().unwrap() + NEED - SPACE;
     let least = dirs.into_iter().filter(|&v| v >= clear).min().unwrap();
-    println!("Clearing away the smallest directory of at least {clear} saves {least} bytes");
+    least.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day07::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(95437));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(24933642));
+    }
 }
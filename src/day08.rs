@@ -1,20 +1,23 @@
-use jungle::readfile;
-
-const SIZE: usize = 99;
+use jungle::{Answer, Contents};
 
+// The forest is square
 struct Grid {
-    height: [[u8; SIZE]; SIZE],
+    height: Vec<Vec<u8>>,
+    size: usize,
 }
 
 impl Grid {
     fn read(text: &str) -> Grid {
-        let mut height = [[0u8; SIZE]; SIZE];
-        for (row, line) in text.lines().enumerate() {
-            for (col, ch) in line.chars().enumerate() {
-                height[row][col] = ch.to_digit(10).unwrap() as u8;
-            }
-        }
-        Grid { height }
+        let height: Vec<Vec<u8>> = text
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .map(|ch| ch.to_digit(10).unwrap() as u8)
+                    .collect()
+            })
+            .collect();
+        let size = height.len();
+        Grid { height, size }
     }
 
     fn viz_up(&self, row: usize, col: usize) -> bool {
@@ -31,7 +34,7 @@ impl Grid {
     fn viz_dn(&self, row: usize, col: usize) -> bool {
         let h = self.height[row][col];
 
-        for r in (row + 1)..SIZE {
+        for r in (row + 1)..self.size {
             if self.height[r][col] >= h {
                 return false;
             }
@@ -53,7 +56,7 @@ impl Grid {
     fn viz_rt(&self, row: usize, col: usize) -> bool {
         let h = self.height[row][col];
 
-        for c in (col + 1)..SIZE {
+        for c in (col + 1)..self.size {
             if self.height[row][c] >= h {
                 return false;
             }
@@ -85,7 +88,7 @@ impl Grid {
         let mut trees = 0;
         let h = self.height[row][col];
 
-        for r in (row + 1)..SIZE {
+        for r in (row + 1)..self.size {
             trees += 1;
             if self.height[r][col] >= h {
                 break;
@@ -111,7 +114,7 @@ impl Grid {
         let mut trees = 0;
         let h = self.height[row][col];
 
-        for c in (col + 1)..SIZE {
+        for c in (col + 1)..self.size {
             trees += 1;
             if self.height[row][c] >= h {
                 break;
@@ -128,31 +131,54 @@ impl Grid {
     }
 }
 
-pub fn a() {
-    let ctxt = readfile("08");
+pub fn a(ctxt: &Contents) -> Answer {
     let grid = Grid::read(&ctxt.text);
-    let mut visible = 0;
-    for row in 0..SIZE {
-        for col in 0..SIZE {
+    let mut visible: usize = 0;
+    for row in 0..grid.size {
+        for col in 0..grid.size {
             if grid.visible(row, col) {
                 visible += 1;
             }
         }
     }
-    println!("{visible} trees visible");
+    visible.into()
 }
 
-pub fn b() {
-    let ctxt = readfile("08");
+pub fn b(ctxt: &Contents) -> Answer {
     let grid = Grid::read(&ctxt.text);
     let mut best = 0;
-    for row in 0..SIZE {
-        for col in 0..SIZE {
+    for row in 0..grid.size {
+        for col in 0..grid.size {
             let s = grid.scenic(row, col);
             if s > best {
                 best = s;
             }
         }
     }
-    println!("Best scenic score was {best}");
+    best.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day08::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(21));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(8));
+    }
 }
@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Default)]
 struct Position {
@@ -33,9 +33,7 @@ fn catch(head: &Position, tail: &mut Position) {
 
 use std::collections::HashSet;
 
-pub fn a() {
-    let ctxt = readfile("09");
-
+pub fn a(ctxt: &Contents) -> Answer {
     let mut head: Position = Default::default();
     let mut tail: Position = Default::default();
 
@@ -79,8 +77,7 @@ pub fn a() {
             }
         }
     }
-    let count = trail.len();
-    println!("{count} distinct positions of the tail");
+    trail.len().into()
 }
 
 fn chase(rope: &mut [Position; 10]) {
@@ -92,9 +89,7 @@ fn chase(rope: &mut [Position; 10]) {
     }
 }
 
-pub fn b() {
-    let ctxt = readfile("09");
-
+pub fn b(ctxt: &Contents) -> Answer {
     let mut knots: [Position; 10] = [Default::default(); 10];
 
     let mut trail = HashSet::<Position>::new();
@@ -137,6 +132,33 @@ pub fn b() {
             }
         }
     }
-    let count = trail.len();
-    println!("{count} distinct positions of the tail");
+    trail.len().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day09::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(13));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(1));
+    }
 }
@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 fn run(lines: &mut dyn Iterator<Item = &str>, max: usize) -> Vec<i64> {
     let mut cycle = 0;
@@ -33,12 +33,11 @@ fn run(lines: &mut dyn Iterator<Item = &str>, max: usize) -> Vec<i64> {
     v
 }
 
-pub fn a() {
+pub fn a(ctxt: &Contents) -> Answer {
     fn strength(x: i64, cycle: usize) -> i64 {
         x * (cycle as i64)
     }
 
-    let ctxt = readfile("10");
     let mut lines = ctxt.lines();
     let cycles = run(&mut lines, 220);
 
@@ -53,24 +52,200 @@ pub fn a() {
         }
     }
 
-    println!("Arbitrary total calculated was: {total}");
+    total.into()
 }
 
-pub fn b() {
-    let ctxt = readfile("10");
+pub fn b(ctxt: &Contents) -> Answer {
     let mut lines = ctxt.lines();
     let cycles = run(&mut lines, 240);
 
+    let mut picture = String::new();
     for (cycle, &x) in cycles.iter().enumerate() {
         // Draw or don't draw sprite
         let col = (cycle % 40) as i64;
         if col + 1 >= x && col - 1 <= x {
-            print!("█");
+            picture.push('█');
         } else {
-            print!(" ");
+            picture.push(' ');
         }
         if col == 39 {
-            println!();
+            picture.push('\n');
         }
     }
+    Answer::Picture(picture)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day10::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop
+";
+
+    const PICTURE: &str = "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(13140));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        let picture = PICTURE.replace('#', "█").replace('.', " ");
+        assert_eq!(b(&ctxt), Answer::Picture(picture));
+    }
 }
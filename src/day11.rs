@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 use std::collections::VecDeque;
 
@@ -90,7 +90,7 @@ impl Monkey {
         // Monkey gets bored
         m /= 3;
 
-        match m % self.divisor == 0 {
+        match m.is_multiple_of(self.divisor) {
             true => (self.if_true, m),
             false => (self.if_false, m),
         }
@@ -112,7 +112,7 @@ impl Monkey {
         // No monkey boredom, so reduce by a chosen factor
         let m = (self.op)(n) % reduce;
 
-        match m % self.divisor == 0 {
+        match m.is_multiple_of(self.divisor) {
             true => (self.if_true, m),
             false => (self.if_false, m),
         }
@@ -142,8 +142,7 @@ impl Monkey {
     }
 }
 
-pub fn a() {
-    let ctxt = readfile("11");
+pub fn a(ctxt: &Contents) -> Answer {
     let mut monkeys = Vec::new();
     let mut lines = ctxt.lines();
     loop {
@@ -164,11 +163,10 @@ pub fn a() {
     let mut v: Vec<usize> = monkeys.into_iter().map(|m| m.activity()).collect();
     v.select_nth_unstable_by(1, |a, b| b.cmp(a));
     let business = v[0] * v[1];
-    println!("Level of monkey business is {business}");
+    business.into()
 }
 
-pub fn b() {
-    let ctxt = readfile("11");
+pub fn b(ctxt: &Contents) -> Answer {
     let mut monkeys = Vec::new();
     let mut lines = ctxt.lines();
     loop {
@@ -190,5 +188,52 @@ pub fn b() {
     let mut v: Vec<usize> = monkeys.into_iter().map(|m| m.activity()).collect();
     v.select_nth_unstable_by(1, |a, b| b.cmp(a));
     let business = v[0] * v[1];
-    println!("Level of monkey business is {business}");
+    business.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day11::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(10605));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(2713310158));
+    }
 }
@@ -1,181 +1,72 @@
-use jungle::readfile;
+use jungle::map::{Map, Markers};
+use jungle::{Answer, Contents};
 
-const WIDTH: usize = 143;
-const HEIGHT: usize = 41;
-
-struct Map {
-    elevation: [[u8; WIDTH]; HEIGHT],
-    dist: [[u16; WIDTH]; HEIGHT],
-    done: [[bool; WIDTH]; HEIGHT],
-    start: (usize, usize),
-    end: (usize, usize),
+fn read_map(ctxt: &Contents) -> (Map<u8>, Markers) {
+    let (map, markers) = Map::parse_marked(&ctxt.text, &['S', 'E'], |ch| match ch {
+        'S' => Ok(b'a'),
+        'E' => Ok(b'z'),
+        'a'..='z' => Ok(ch as u8),
+        _ => Err("not an elevation"),
+    })
+    .expect("Map should only have elevations");
+    (map, markers)
 }
 
-impl Default for Map {
-    fn default() -> Self {
-        let elevation = [[0u8; WIDTH]; HEIGHT];
-        let dist = [[0u16; WIDTH]; HEIGHT];
-        let done = [[false; WIDTH]; HEIGHT];
-        Self {
-            elevation,
-            dist,
-            done,
-            start: (0, 0),
-            end: (0, 0),
-        }
-    }
+// We climb at most one step up but can descend any distance, however we're measuring distances
+// backwards from the end so the check is reversed
+fn check_height(from: u8, to: u8) -> bool {
+    to + 1 >= from
 }
 
-use std::fmt;
-impl fmt::Debug for Map {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str("===========================\n")?;
-        for row in 0..HEIGHT {
-            for col in 0..WIDTH {
-                if (row, col) == self.start {
-                    f.write_str("S")?
-                } else if (row, col) == self.end {
-                    f.write_str("E")?
-                } else {
-                    let c = self.elevation[row][col] as char;
-                    f.write_fmt(format_args!("{}", c))?;
-                }
-            }
-            f.write_str("\n")?;
-        }
-        f.write_str("---------------------------\n")?;
-        for row in 0..HEIGHT {
-            for col in 0..WIDTH {
-                if (row, col) == self.start {
-                    f.write_str("S")?
-                } else if (row, col) == self.end {
-                    f.write_str("E")?
-                } else {
-                    let c = char::from_u32(self.dist[row][col] as u32 + 60).unwrap();
-                    f.write_fmt(format_args!("{}", c))?;
-                }
-            }
-            f.write_str("\n")?;
-        }
-        Ok(())
-    }
+// Fewest steps from each position to the end
+fn flood(map: &Map<u8>, markers: &Markers) -> Map<Option<u32>> {
+    map.distances(&[markers[&'E']], check_height)
 }
 
-fn check_height(from: u8, to: u8) -> bool {
-    from + 1 >= to
+pub fn a(ctxt: &Contents) -> Answer {
+    let (map, markers) = read_map(ctxt);
+    let dist = flood(&map, &markers);
+    let (x, y) = markers[&'S'];
+    let shortest = dist
+        .read(x, y)
+        .flatten()
+        .expect("Should be able to reach the end");
+    shortest.into()
 }
 
-impl Map {
-    fn read(text: &str) -> Self {
-        let mut elevation = [[0u8; WIDTH]; HEIGHT];
-        let mut start = (0, 0);
-        let mut end = (0, 0);
-        for (row, line) in text.lines().enumerate() {
-            for (col, byte) in line.bytes().enumerate() {
-                elevation[row][col] = match byte {
-                    b'S' => {
-                        start = (row, col);
-                        b'a'
-                    }
-                    b'E' => {
-                        end = (row, col);
-                        b'z'
-                    }
-                    b'a'..=b'z' => byte,
-                    _ => {
-                        panic!("Unexpected map byte: {byte}")
-                    }
-                };
-            }
-        }
-        Self {
-            elevation,
-            start,
-            end,
-            ..Default::default()
-        }
-    }
-
-    fn height(&self, row: usize, col: usize) -> u8 {
-        self.elevation[row][col]
-    }
-
-    fn step(&mut self, dist: u16, todo: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
-        let mut v = Vec::new();
-        for (row, col) in todo {
-            self.dist[row][col] = dist;
-            self.done[row][col] = true;
+pub fn b(ctxt: &Contents) -> Answer {
+    let (map, markers) = read_map(ctxt);
+    let dist = flood(&map, &markers);
+    let best = map
+        .iter()
+        .filter(|&(_, height)| height == b'a')
+        .filter_map(|((x, y), _)| dist.read(x, y).flatten())
+        .min()
+        .expect("Should be able to reach the end from some lowest square");
+    best.into()
+}
 
-            if row > 0
-                && !self.done[row - 1][col]
-                && check_height(self.height(row - 1, col), self.height(row, col))
-            {
-                v.push((row - 1, col));
-            }
-            if row + 1 < HEIGHT
-                && !self.done[row + 1][col]
-                && check_height(self.height(row + 1, col), self.height(row, col))
-            {
-                v.push((row + 1, col));
-            }
-            if col > 0
-                && !self.done[row][col - 1]
-                && check_height(self.height(row, col - 1), self.height(row, col))
-            {
-                v.push((row, col - 1));
-            }
-            if col + 1 < WIDTH
-                && !self.done[row][col + 1]
-                && check_height(self.height(row, col + 1), self.height(row, col))
-            {
-                v.push((row, col + 1));
-            }
-        }
-        v
-    }
+#[cfg(test)]
+mod tests {
+    use crate::day12::{a, b};
+    use jungle::{Answer, Contents};
 
-    fn flood(&mut self) {
-        let mut dist = 0;
-        let mut v = Vec::new();
-        v.push((self.end.0, self.end.1));
-        while !v.is_empty() {
-            v = self.step(dist, v);
-            v.sort_unstable();
-            v.dedup();
-            dist += 1;
-        }
-    }
+    const EXAMPLE: &str = "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi
+";
 
-    fn shortest(self) -> u16 {
-        self.dist[self.start.0][self.start.1]
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(31));
     }
 
-    fn best(self) -> u16 {
-        let mut dist = u16::MAX;
-        for row in 0..HEIGHT {
-            for col in 0..WIDTH {
-                if self.height(row, col) == b'a'
-                    && self.done[row][col]
-                    && self.dist[row][col] < dist
-                {
-                    dist = self.dist[row][col];
-                }
-            }
-        }
-        dist
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(29));
     }
 }
-
-pub fn a() {
-    let ctxt = readfile("12");
-    let mut map = Map::read(&ctxt.text);
-    map.flood();
-    println!("Shortest route from S is: {} steps", map.shortest());
-}
-
-pub fn b() {
-    let ctxt = readfile("12");
-    let mut map = Map::read(&ctxt.text);
-    map.flood();
-    println!("Best distance is: {}", map.best());
-}
@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 use core::cmp::Ordering;
 
@@ -8,7 +8,7 @@ enum Distress<'t> {
     Integer(&'t str),
 }
 
-fn item(mut s: &str) -> Option<(Distress, &str)> {
+fn item(mut s: &str) -> Option<(Distress<'_>, &str)> {
     const OPEN: u8 = b'[';
     const CLOSE: u8 = b']';
     const COMMA: u8 = b',';
@@ -47,7 +47,7 @@ fn item(mut s: &str) -> Option<(Distress, &str)> {
                 return Some((Distress::Integer(substr), rest));
             }
         }
-        return Some((Distress::Integer(s), ""));
+        Some((Distress::Integer(s), ""))
     }
 }
 
@@ -97,8 +97,7 @@ fn compare(mut left: &str, mut right: &str) -> Ordering {
     }
 }
 
-pub fn a() {
-    let ctxt = readfile("13");
+pub fn a(ctxt: &Contents) -> Answer {
     let mut lines = ctxt.lines();
     let mut sum = 0;
     let mut group = 0;
@@ -119,15 +118,13 @@ pub fn a() {
             break;
         }
     }
-    println!("Sum of indices of correctly ordered packets is {sum}");
+    sum.into()
 }
 
-pub fn b() {
+pub fn b(ctxt: &Contents) -> Answer {
     const DIV_A: &str = "[[2]]";
     const DIV_B: &str = "[[6]]";
 
-    let ctxt = readfile("13");
-
     let mut v: Vec<&str> = ctxt.lines().filter(|l| !l.is_empty()).collect();
     v.push(DIV_A);
     v.push(DIV_B);
@@ -145,5 +142,48 @@ pub fn b() {
             pos_b = count;
         }
     }
-    println!("Decoder key is: {}", pos_a * pos_b);
+    (pos_a * pos_b).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day13::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(13));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(140));
+    }
 }
@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 type Symbol = u8;
 
@@ -173,23 +173,43 @@ impl Cave {
     }
 }
 
-pub fn a() {
-    let ctxt = readfile("14");
+pub fn a(ctxt: &Contents) -> Answer {
     let mut cave: Cave = Default::default();
     for line in ctxt.lines() {
         cave.draw(line);
     }
     let count = cave.fill();
-    println!("{count} units of sand will come to rest");
+    count.into()
 }
 
-pub fn b() {
-    let ctxt = readfile("14");
+pub fn b(ctxt: &Contents) -> Answer {
     let mut cave: Cave = Default::default();
     for line in ctxt.lines() {
         cave.draw(line);
     }
     cave.draw_base();
     let count = cave.fill();
-    println!("{count} units of sand will come to rest");
+    count.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day14::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(24));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(93));
+    }
 }
@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 #[derive(Copy, Clone, Debug)]
 struct Coord {
@@ -170,39 +170,36 @@ impl Overlap {
     }
 }
 
-const DISTANCE: isize = 2_000_000;
-
-pub fn a() {
-    let ctxt = readfile("15");
-
+// Positions in row y where the distress beacon cannot be
+fn misses(ctxt: &Contents, y: isize) -> isize {
     let mut overlap = Overlap::new();
     for line in ctxt.lines() {
         let (sensor, beacon) = parse(line);
-        let (left, right) = consider(&sensor, &beacon, DISTANCE);
+        let (left, right) = consider(&sensor, &beacon, y);
         overlap.add(left, right);
     }
     let mut misses = 0;
     for x in overlap.v {
         misses += x.1 - x.0 + 1;
     }
-    println!(
-        "There are {} positions which cannot contain a beacon",
-        misses - 1
-    );
+    misses - 1
 }
 
-const MAX_RANGE: isize = 4_000_000;
+const DISTANCE: isize = 2_000_000;
 
-pub fn b() {
-    let ctxt = readfile("15");
+pub fn a(ctxt: &Contents) -> Answer {
+    misses(ctxt, DISTANCE).into()
+}
 
+// Tuning frequency of the only position for the distress beacon within the range
+fn frequency(ctxt: &Contents, range: isize) -> isize {
     let mut v = Vec::new();
 
     for line in ctxt.lines() {
         let (sensor, beacon) = parse(line);
         v.push((sensor, beacon));
     }
-    for y in 0..MAX_RANGE {
+    for y in 0..range {
         let mut overlap = Overlap::new();
         for (sensor, beacon) in v.iter() {
             let (left, right) = consider(sensor, beacon, y);
@@ -210,10 +207,49 @@ pub fn b() {
                 overlap.add(left, right);
             }
         }
-        if let Some(x) = overlap.hole(0, MAX_RANGE) {
-            println!("x = {x}, y = {y}");
-            println!("Therefore frequency = {}", (4000000 * x) + y);
-            return;
+        if let Some(x) = overlap.hole(0, range) {
+            return (4000000 * x) + y;
         }
     }
+    panic!("There should be exactly one position for the distress beacon");
+}
+
+const MAX_RANGE: isize = 4_000_000;
+
+pub fn b(ctxt: &Contents) -> Answer {
+    frequency(ctxt, MAX_RANGE).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day15::{frequency, misses};
+    use jungle::Contents;
+
+    const EXAMPLE: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(misses(&ctxt, 10), 26);
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(frequency(&ctxt, 20), 56000011);
+    }
 }
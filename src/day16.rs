@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents, Optimise};
 use std::collections::HashMap;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -104,7 +104,7 @@ impl Map {
         v.reverse();
         for t in 0..REMAINING {
             let mut remaining = 0;
-            for k in 0..((t + 1) / 2) {
+            for k in 0..t.div_ceil(2) {
                 if k < v.len() {
                     let since = t - (k * 2);
                     remaining += (v[k] as u32) * (since as u32);
@@ -131,7 +131,7 @@ impl Visitor {
         }
     }
 
-    fn next(&self, time: u32, at: &Valve) -> (Vec<Self>, Vec<Self>) {
+    fn moves(&self, time: u32, at: &Valve) -> (Vec<Self>, Vec<Self>) {
         let mut out = Vec::new();
         let mut solutions = Vec::new();
         if let Some(n) = at.openid {
@@ -182,45 +182,28 @@ fn parse(s: &str) -> (ValveId, Valve) {
     (v, valve)
 }
 
-fn part1(map: &Map) -> u32 {
-    let mut time = 30;
-    let mut current: Vec<Visitor> = vec![Visitor::new()];
-
-    loop {
-        time -= 1;
-        let mut next: Vec<Visitor> = Vec::new();
-
-        for possible in current {
-            let x = map.get(possible.at);
-            if let Some(valve) = x {
-                let (onward, solutions) = possible.next(time, valve);
-                for maybe in onward {
-                    next.push(maybe);
-                }
-                for maybe in solutions {
-                    next.push(maybe);
-                }
-            } else {
-                panic!("Somehow {:?} is not on the map", possible.at);
-            }
-        }
-
-        next.sort_unstable();
-        let best = next.last().unwrap().released;
-        let remain = map.remainder(time);
-        let need = if remain > best { 0 } else { best - remain };
+impl Optimise<Map> for Visitor {
+    fn next(&self, remaining: usize, map: &Map) -> Vec<Self> {
+        let Some(valve) = map.get(self.at) else {
+            panic!("Somehow {:?} is not on the map", self.at);
+        };
+        let (onward, _) = self.moves(remaining as u32, valve);
+        onward
+    }
 
-        // Eliminate possibilities that can't get enough pressure to beat the leader
-        next.retain(|&maybe| maybe.released >= need);
-        next.dedup();
+    fn score(&self, _remaining: usize, _map: &Map) -> usize {
+        self.released as usize
+    }
 
-        current = next;
-        if time == 0 {
-            break;
-        }
+    // Released so far plus the most pressure which could be released by the remaining valves
+    fn bound(&self, remaining: usize, map: &Map) -> usize {
+        (self.released + map.remainder(remaining as u32)) as usize
     }
+}
 
-    current.last().unwrap().released
+fn part1(map: &Map) -> u32 {
+    let (best, _) = Visitor::optimise(Visitor::new(), 30, map, None);
+    best as u32
 }
 
 fn part2(map: &Map) -> u32 {
@@ -236,7 +219,7 @@ fn part2(map: &Map) -> u32 {
         for possible in current {
             let x = map.get(possible.at);
             if let Some(valve) = x {
-                let (onward, solutions) = possible.next(time, valve);
+                let (onward, solutions) = possible.moves(time, valve);
                 for maybe in onward {
                     next.push(maybe);
                 }
@@ -282,10 +265,9 @@ fn part2(map: &Map) -> u32 {
     best
 }
 
-pub fn a() {
+pub fn a(ctxt: &Contents) -> Answer {
     let mut openable = 0;
     let mut map: Map = Default::default();
-    let ctxt = readfile("16");
     for line in ctxt.lines() {
         let (id, mut valve) = parse(line);
         if valve.flow > 0 {
@@ -296,13 +278,12 @@ pub fn a() {
     }
     map.calculate();
     let n = part1(&map);
-    println!("Most pressure released is {n}");
+    n.into()
 }
 
-pub fn b() {
+pub fn b(ctxt: &Contents) -> Answer {
     let mut openable = 0;
     let mut map: Map = Default::default();
-    let ctxt = readfile("16");
     for line in ctxt.lines() {
         let (id, mut valve) = parse(line);
         if valve.flow > 0 {
@@ -313,5 +294,35 @@ pub fn b() {
     }
     map.calculate();
     let n = part2(&map);
-    println!("Working with an elephant, most pressure released is {n}");
+    n.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day16::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+Valve BB has flow rate=13; tunnels lead to valves CC, AA
+Valve CC has flow rate=2; tunnels lead to valves DD, BB
+Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+Valve EE has flow rate=3; tunnels lead to valves FF, DD
+Valve FF has flow rate=0; tunnels lead to valves EE, GG
+Valve GG has flow rate=0; tunnels lead to valves FF, HH
+Valve HH has flow rate=22; tunnel leads to valve GG
+Valve II has flow rate=0; tunnels lead to valves AA, JJ
+Valve JJ has flow rate=21; tunnel leads to valve II
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(1651));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(1707));
+    }
 }
@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 type Line = [u8; 7];
 
@@ -270,7 +270,7 @@ use std::fmt;
 impl fmt::Debug for Chamber {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let l = self.layers.len();
-        let n = if l > 20 { l - 20 } else { 0 };
+        let n = l.saturating_sub(20);
         for row in (n..l).rev() {
             f.write_str("|")?;
             for col in 0..7 {
@@ -290,8 +290,7 @@ impl fmt::Debug for Chamber {
 const LEFT: u8 = b'<';
 const RIGHT: u8 = b'>';
 
-pub fn a() {
-    let ctxt = readfile("17");
+pub fn a(ctxt: &Contents) -> Answer {
     let mut chamber = Chamber::new();
 
     let mut bytes = ctxt.text.trim().bytes().cycle();
@@ -301,13 +300,12 @@ pub fn a() {
         let mut dropper = Dropper::new(Rock::new(k), start);
         dropper.drop(&mut chamber, &mut bytes);
     }
-    println!("Tower of rocks is {} units tall", chamber.true_height());
+    chamber.true_height().into()
 }
 
 const TARGET: usize = 1_000_000_000_000;
 
-pub fn b() {
-    let ctxt = readfile("17");
+pub fn b(ctxt: &Contents) -> Answer {
     let mut chamber = Chamber::new();
 
     let jet_cycle = ctxt.text.trim();
@@ -410,15 +408,33 @@ pub fn b() {
         chamber.fix();
         let start = chamber.height() + 3;
         let mut dropper = Dropper::new(Rock::new(rocks), start);
-        clock += dropper.drop(&mut chamber, &mut bytes);
+        dropper.drop(&mut chamber, &mut bytes);
 
         rocks += 1;
         if rocks == TARGET {
             break;
         }
     }
-    println!(
-        "After all {TARGET} rocks, the tower ends up {} tall",
-        skipped_height + chamber.true_height()
-    );
+    (skipped_height + chamber.true_height()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day17::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(3068));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(1514285714288));
+    }
 }
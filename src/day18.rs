@@ -1,185 +1,117 @@
-use jungle::readfile;
+use jungle::grid::Grid;
+use jungle::{Answer, Contents};
 
-fn parse(line: &str) -> (usize, usize, usize) {
+type Coord3 = [isize; 3];
+
+fn parse(line: &str) -> Coord3 {
     let c: [&str; 3] = line
         .split(',')
         .collect::<Vec<_>>()
         .try_into()
         .expect("3D co-ordinates should have exactly three elements");
-    let x: usize = c[0].parse().unwrap();
-    let y: usize = c[1].parse().unwrap();
-    let z: usize = c[2].parse().unwrap();
-    (x, y, z)
+    c.map(|n| n.parse().unwrap())
 }
 
-fn extents(text: &str) -> (usize, usize, usize) {
-    let mut max_x = 0;
-    let mut max_y = 0;
-    let mut max_z = 0;
-    for line in text.lines() {
-        let (x, y, z) = parse(line);
-        if x > max_x {
-            max_x = x;
-        }
-        if y > max_y {
-            max_y = y;
-        }
-        if z > max_z {
-            max_z = z;
-        }
+fn droplet(ctxt: &Contents) -> Grid<bool, 3> {
+    let mut grid = Grid::new();
+    for line in ctxt.lines() {
+        grid.write(parse(line), true);
     }
-    (max_x, max_y, max_z)
+    grid
 }
 
-const GRID_SIZE: usize = 32;
-
-type Coord3 = (usize, usize, usize);
-
-struct Crude {
-    grid: [[[bool; GRID_SIZE]; GRID_SIZE]; GRID_SIZE],
-    water: [[[bool; GRID_SIZE]; GRID_SIZE]; GRID_SIZE],
-}
-
-impl Default for Crude {
-    fn default() -> Self {
-        let empty = [[[false; GRID_SIZE]; GRID_SIZE]; GRID_SIZE];
-        Crude {
-            grid: empty,
-            water: empty,
-        }
-    }
+// Faces of each cube touching anything else
+fn surface(grid: &Grid<bool, 3>) -> usize {
+    grid.find(|b| b)
+        .into_iter()
+        .map(|cube| {
+            grid.neighbours(cube)
+                .filter(|&(_, b)| b != Some(true))
+                .count()
+        })
+        .sum()
 }
 
-impl Crude {
-    fn set(&mut self, x: usize, y: usize, z: usize) {
-        self.grid[x][y][z] = true;
-    }
-
-    fn big_enough(&self, x: usize, y: usize, z: usize) -> bool {
-        x + 2 < GRID_SIZE && y + 2 < GRID_SIZE && z + 2 < GRID_SIZE
-    }
-
-    fn count(&self, x: usize, y: usize, z: usize) -> usize {
-        if self.check(x, y, z) {
-            let mut count = 0;
-            count += if self.check(x, y, z - 1) { 0 } else { 1 };
-            count += if self.check(x, y - 1, z) { 0 } else { 1 };
-            count += if self.check(x - 1, y, z) { 0 } else { 1 };
-            count += if self.check(x, y, z + 1) { 0 } else { 1 };
-            count += if self.check(x, y + 1, z) { 0 } else { 1 };
-            count += if self.check(x + 1, y, z) { 0 } else { 1 };
-            count
-        } else {
-            0
-        }
-    }
-
-    // For symmetry with the count method write it this way
-    #[allow(clippy::bool_to_int_with_if)]
-    fn count_water(&self, x: usize, y: usize, z: usize) -> usize {
-        if self.check(x, y, z) {
-            let mut count = 0;
-            count += if self.water[x][y][z - 1] { 1 } else { 0 };
-            count += if self.water[x][y - 1][z] { 1 } else { 0 };
-            count += if self.water[x - 1][y][z] { 1 } else { 0 };
-            count += if self.water[x][y][z + 1] { 1 } else { 0 };
-            count += if self.water[x][y + 1][z] { 1 } else { 0 };
-            count += if self.water[x + 1][y][z] { 1 } else { 0 };
-
-            count
-        } else {
-            0
-        }
-    }
-
-    fn check(&self, x: usize, y: usize, z: usize) -> bool {
-        self.grid[x][y][z]
-    }
-
-    fn surface(&self) -> usize {
-        let mut count = 0;
-        for x in 1..(GRID_SIZE - 1) {
-            for y in 1..(GRID_SIZE - 1) {
-                for z in 1..(GRID_SIZE - 1) {
-                    count += self.count(x, y, z);
+// Water floods around the droplet from just outside one corner
+fn flood(grid: &Grid<bool, 3>) -> Grid<bool, 3> {
+    let lo: Coord3 = std::array::from_fn(|k| grid.axis(k).start() - 1);
+    let hi: Coord3 = std::array::from_fn(|k| grid.axis(k).end() + 1);
+    let inside = |posn: Coord3| (0..3).all(|k| (lo[k]..=hi[k]).contains(&posn[k]));
+
+    let mut water = Grid::new();
+    water.write(lo, true);
+    let mut queue = vec![lo];
+    while !queue.is_empty() {
+        let mut next = Vec::new();
+        for posn in queue {
+            for (posn, wet) in water.neighbours(posn) {
+                if wet != Some(true) && inside(posn) && grid.read(posn) != Some(true) {
+                    next.push(posn);
                 }
             }
         }
-        count
-    }
-
-    fn exterior(&self) -> usize {
-        let mut count = 0;
-        for x in 1..(GRID_SIZE - 1) {
-            for y in 1..(GRID_SIZE - 1) {
-                for z in 1..(GRID_SIZE - 1) {
-                    count += self.count_water(x, y, z);
-                }
-            }
+        for &posn in next.iter() {
+            water.write(posn, true);
         }
-        count
+        next.sort_unstable();
+        next.dedup();
+        queue = next;
     }
+    water
+}
 
-    fn flood(&mut self) {
-        let mut queue: Vec<Coord3> = Vec::new();
-        queue.push((0, 0, 0));
-        while !queue.is_empty() {
-            let mut next: Vec<Coord3> = Vec::new();
-            for (x, y, z) in queue {
-                if !self.check(x, y, z) && !self.water[x][y][z] {
-                    self.water[x][y][z] = true;
-                    if x > 0 {
-                        next.push((x - 1, y, z));
-                    }
-                    if y > 0 {
-                        next.push((x, y - 1, z));
-                    }
-                    if z > 0 {
-                        next.push((x, y, z - 1));
-                    }
-                    if x + 1 < GRID_SIZE {
-                        next.push((x + 1, y, z));
-                    }
-                    if y + 1 < GRID_SIZE {
-                        next.push((x, y + 1, z));
-                    }
-                    if z + 1 < GRID_SIZE {
-                        next.push((x, y, z + 1));
-                    }
-                }
-            }
-            queue = next;
-        }
-    }
+// Faces of each cube touching the water outside
+fn exterior(grid: &Grid<bool, 3>, water: &Grid<bool, 3>) -> usize {
+    grid.find(|b| b)
+        .into_iter()
+        .map(|cube| {
+            grid.neighbours(cube)
+                .filter(|&(posn, _)| water.read(posn) == Some(true))
+                .count()
+        })
+        .sum()
 }
 
-pub fn a() {
-    let ctxt = readfile("18");
-    let (mx, my, mz) = extents(ctxt.value());
-    let mut crude: Crude = Default::default();
-    if !crude.big_enough(mx, my, mz) {
-        panic!("Input extent {mx},{my},{mz} is too large for our grid");
-    }
-    for line in ctxt.lines() {
-        let (x, y, z) = parse(line);
-        crude.set(x + 1, y + 1, z + 1);
-    }
+pub fn a(ctxt: &Contents) -> Answer {
+    let grid = droplet(ctxt);
+    surface(&grid).into()
+}
 
-    println!("Surface area calculated as: {}", crude.surface());
+pub fn b(ctxt: &Contents) -> Answer {
+    let grid = droplet(ctxt);
+    let water = flood(&grid);
+    exterior(&grid, &water).into()
 }
 
-pub fn b() {
-    let ctxt = readfile("18");
-    let (mx, my, mz) = extents(ctxt.value());
-    let mut crude: Crude = Default::default();
-    if !crude.big_enough(mx, my, mz) {
-        panic!("Input extent {mx},{my},{mz} is too large for our grid");
-    }
-    for line in ctxt.lines() {
-        let (x, y, z) = parse(line);
-        crude.set(x + 1, y + 1, z + 1);
+#[cfg(test)]
+mod tests {
+    use crate::day18::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "2,2,2
+1,2,2
+3,2,2
+2,1,2
+2,3,2
+2,2,1
+2,2,3
+2,2,4
+2,2,6
+1,2,5
+3,2,5
+2,1,5
+2,3,5
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(64));
     }
 
-    crude.flood();
-    println!("Exterior surface area calculated as: {}", crude.exterior());
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(58));
+    }
 }
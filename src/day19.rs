@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents, Optimise};
 
 type Number = u8;
 
@@ -75,10 +75,20 @@ impl Me {
         self.obsidian += self.obsidian_robot;
         self.geode += self.geode_robot;
     }
+}
 
-    fn next(&self, print: &Blueprint) -> Vec<Self> {
+impl Optimise<Blueprint> for Me {
+    fn next(&self, remaining: usize, print: &Blueprint) -> Vec<Self> {
         let mut out = Vec::new();
 
+        // Making any robot in our last minute is futile
+        if remaining == 0 {
+            let mut mined = *self;
+            mined.harvest();
+            out.push(mined);
+            return out;
+        }
+
         // Make an ore robot if we have enough resource and we may need more ore
         if self.ore >= print.ore && self.ore_robot < print.most_ore {
             let mut make = *self;
@@ -127,11 +137,20 @@ impl Me {
 
         out
     }
+
+    // Geodes opened by the end if no more robots are made
+    fn score(&self, remaining: usize, _print: &Blueprint) -> usize {
+        self.geode as usize + self.geode_robot as usize * remaining
+    }
+
+    fn bound(&self, remaining: usize, _print: &Blueprint) -> usize {
+        self.geode as usize + extra(self.geode_robot as usize, remaining)
+    }
 }
 
 // How many extra geodes could possibly get opened
 // Assume each day one extra robot is available (no more could possibly be made)
-fn extra(mut n: Number, t: Number) -> Number {
+fn extra(mut n: usize, t: usize) -> usize {
     let mut sum = 0;
     for _ in 0..t {
         sum += n;
@@ -140,56 +159,69 @@ fn extra(mut n: Number, t: Number) -> Number {
     sum
 }
 
-fn run(print: &Blueprint, mut time: Number) -> Number {
-    let mut current: Vec<Me> = Vec::new();
-    current.push(Me::new());
-
-    loop {
-        time -= 1;
-
-        let mut next: Vec<Me> = Vec::with_capacity(current.len());
-        for maybe in current {
-            next.append(&mut maybe.next(print));
-        }
-        next.select_nth_unstable_by(0, |a, b| b.cmp(a));
-        let best = next.first().unwrap();
-        if time == 1 {
-            // Making any robot in our last minute is futile
-            return best.geode + best.geode_robot;
-        }
-        let target = best.geode + (best.geode_robot * time);
-        if target > 0 {
-            next.retain(|&maybe| maybe.geode + extra(maybe.geode_robot, time) >= target);
-        }
-
-        next.sort_unstable();
-        next.dedup();
-        current = next;
-    }
+fn run(print: &Blueprint, time: Number) -> Number {
+    let (best, _) = Me::optimise(Me::new(), time as usize, print, None);
+    best as Number
 }
 
 fn quality(print: &Blueprint) -> u32 {
     run(print, 24) as u32 * print.num as u32
 }
 
-pub fn a() {
-    let ctxt = readfile("19");
+pub fn a(ctxt: &Contents) -> Answer {
     let mut sum = 0;
     for line in ctxt.lines() {
         let print: Blueprint = line.parse().unwrap();
         let q = quality(&print);
         sum += q;
     }
-    println!("Adding up quality for all blueprints: {sum}");
+    sum.into()
 }
 
-pub fn b() {
-    let ctxt = readfile("19");
+pub fn b(ctxt: &Contents) -> Answer {
     let mut product = 1;
     for line in ctxt.lines().take(3) {
         let print: Blueprint = line.parse().unwrap();
         let best = run(&print, 32) as u32;
         product *= best;
     }
-    println!("Product of best geode production for three blueprints: {product}");
+    product.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day19::{a, b, extra, Blueprint, Me};
+    use jungle::{Answer, Contents, Optimise};
+
+    const EXAMPLE: &str = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(33));
+    }
+
+    #[test]
+    fn geodes() {
+        // Over part b's 32 minutes the counts no longer fit in a Number
+        let print: Blueprint = EXAMPLE.lines().next().unwrap().parse().unwrap();
+        assert_eq!(extra(0, 32), 496);
+        assert_eq!(Me::new().bound(32, &print), 496);
+        let busy = Me {
+            geode: 20,
+            geode_robot: 9,
+            ..Me::new()
+        };
+        assert_eq!(busy.score(31, &print), 299);
+        assert_eq!(busy.bound(31, &print), 20 + extra(9, 31));
+    }
+
+    #[test]
+    #[ignore = "takes more than a minute in a debug build, seconds with --release"]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(3472));
+    }
 }
@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 use std::collections::VecDeque;
 
@@ -53,26 +53,56 @@ impl List {
     }
 }
 
-pub fn a() {
-    let ctxt = readfile("20");
-    let input: Vec<isize> = ctxt.numbers().collect();
+pub fn a(ctxt: &Contents) -> Answer {
+    let input: Vec<isize> = ctxt
+        .try_numbers()
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|e| panic!("{e}"));
     let mut circle: List = List::new(input);
     circle.mix();
 
     let (x, y, z) = circle.coords();
-    let sum = x + y + z;
-    println!("Sum is {x} + {y} + {z} = {sum}");
+    (x + y + z).into()
 }
 
-pub fn b() {
-    let ctxt = readfile("20");
-    let input: Vec<isize> = ctxt.numbers().map(|n| n * 811589153).collect();
+pub fn b(ctxt: &Contents) -> Answer {
+    let input: Vec<isize> = ctxt
+        .try_numbers()
+        .map(|n| n.map(|n| n * 811589153))
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|e| panic!("{e}"));
     let mut circle: List = List::new(input);
     for _ in 0..10 {
         circle.mix();
     }
 
     let (x, y, z) = circle.coords();
-    let sum = x + y + z;
-    println!("Sum is {x} + {y} + {z} = {sum}");
+    (x + y + z).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day20::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "1
+2
+-3
+3
+-2
+0
+4
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(3));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(1623178306));
+    }
 }
@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 type Num = i64;
 
@@ -267,8 +267,7 @@ impl Troupe {
     }
 }
 
-pub fn a() {
-    let ctxt = readfile("21");
+pub fn a(ctxt: &Contents) -> Answer {
     let mut troupe = Troupe::new();
     for line in ctxt.lines() {
         troupe.add(line);
@@ -279,11 +278,10 @@ pub fn a() {
         }
     }
     let root: MonkeyId = "root".parse().unwrap();
-    println!("Root monkey number is: {}", troupe.get(root).unwrap());
+    troupe.get(root).unwrap().into()
 }
 
-pub fn b() {
-    let ctxt = readfile("21");
+pub fn b(ctxt: &Contents) -> Answer {
     let mut troupe = Troupe::new();
     for line in ctxt.lines() {
         troupe.add(line);
@@ -299,5 +297,40 @@ pub fn b() {
     }
 
     let input = troupe.trace(root);
-    println!("We should yell {input} to pass the equality test");
+    input.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day21::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(152));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(301));
+    }
 }
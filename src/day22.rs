@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 const HEIGHT: usize = 200;
 const WIDTH: usize = 150;
@@ -363,8 +363,7 @@ fn password(position: (usize, usize, Direction)) -> usize {
     1000 * (position.0 + 1) + 4 * (position.1 + 1) + position.2.value()
 }
 
-pub fn a() {
-    let ctxt = readfile("22");
+pub fn a(ctxt: &Contents) -> Answer {
     let mut map = Map::new();
     let mut lines = ctxt.lines();
     let mut row = 0;
@@ -380,12 +379,10 @@ pub fn a() {
     let steps = lines.next().unwrap();
     map.chase(steps);
     let position = map.report();
-    let password = password(position);
-    println!("Password is apparently {password}");
+    password(position).into()
 }
 
-pub fn b() {
-    let ctxt = readfile("22");
+pub fn b(ctxt: &Contents) -> Answer {
     let mut map = Map::new();
     let mut lines = ctxt.lines();
     let mut row = 0;
@@ -401,6 +398,22 @@ pub fn b() {
     let steps = lines.next().unwrap();
     map.cube_chase(steps);
     let position = map.report();
-    let password = password(position);
-    println!("Password is apparently {password}");
+    password(position).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day22::a;
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "        ...#\n        .#..\n        #...\n        ....\n...#.......#\n........#...\n..#....#....\n..........#.\n        ...#....\n        .....#..\n        .#......\n        ......#.\n\n10R5L5R10L4R5L5\n";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(6032));
+    }
+
+    // No example_b, cube_forward hard-codes how the real input's net of 50 wide faces folds into
+    // a cube, and the example's 4 wide net is laid out differently so it can't be walked
 }
@@ -1,5 +1,5 @@
 use jungle::map::Map;
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 #[derive(Copy, Clone, Default, Eq, PartialEq)]
 enum Elf {
@@ -37,14 +37,7 @@ impl fmt::Debug for Elf {
 type Ground = Map<Elf>;
 
 fn crowded(map: &Ground, x: isize, y: isize) -> bool {
-    map.read(x - 1, y - 1).unwrap_or_default() == Here
-        || map.read(x, y - 1).unwrap_or_default() == Here
-        || map.read(x + 1, y - 1).unwrap_or_default() == Here
-        || map.read(x - 1, y).unwrap_or_default() == Here
-        || map.read(x + 1, y).unwrap_or_default() == Here
-        || map.read(x - 1, y + 1).unwrap_or_default() == Here
-        || map.read(x, y + 1).unwrap_or_default() == Here
-        || map.read(x + 1, y + 1).unwrap_or_default() == Here
+    map.neighbours8(x, y).any(|(_, elf)| elf == Some(Here))
 }
 
 fn direction(map: &mut Ground, x: isize, y: isize, d: Elf, dx: isize, dy: isize) -> bool {
@@ -144,8 +137,7 @@ fn step(map: &mut Ground, offset: usize) -> bool {
     motion(map) > 0
 }
 
-fn read_map(filename: &str) -> Ground {
-    let ctxt = readfile(filename);
+fn read_map(ctxt: &Contents) -> Ground {
     let mut map = Map::new();
     for (y, line) in ctxt.lines().enumerate() {
         for (x, byte) in line.bytes().enumerate() {
@@ -165,45 +157,20 @@ fn read_map(filename: &str) -> Ground {
     map
 }
 
-pub fn a() {
-    let mut map: Ground = read_map("23");
-    // Number of elves doesn't change
-    let n = map.count(|&&elf| elf == Here) as isize;
-
+pub fn a(ctxt: &Contents) -> Answer {
+    let mut map: Ground = read_map(ctxt);
     for k in 0..10 {
         step(&mut map, k);
     }
 
-    let elves = map.find(|elf| elf != Empty);
-    let mut min_x = isize::MAX;
-    let mut max_x = isize::MIN;
-    let mut min_y = isize::MAX;
-    let mut max_y = isize::MIN;
-    for (x, y) in elves {
-        if x < min_x {
-            min_x = x;
-        }
-        if y < min_y {
-            min_y = y;
-        }
-        if y > max_y {
-            max_y = y;
-        }
-        if x > max_x {
-            max_x = x;
-        }
-    }
-
-    let width = (max_x - min_x) + 1;
-    let height = (max_y - min_y) + 1;
-    println!(
-        "{} Empty ground tiles in the smallest containing rectangle",
-        (width * height) - n
-    );
+    let (x, y) = map
+        .bounding_box_of(|elf| elf == Here)
+        .expect("There should be elves");
+    map.crop(x, y).count(|&&elf| elf == Empty).into()
 }
 
-pub fn b() {
-    let mut map: Ground = read_map("23");
+pub fn b(ctxt: &Contents) -> Answer {
+    let mut map: Ground = read_map(ctxt);
 
     let mut k = 0;
     let steps = loop {
@@ -213,5 +180,31 @@ pub fn b() {
         k += 1;
     };
 
-    println!("Elves don't go anywhere on move #{steps}");
+    steps.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day23::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = ".....
+..##.
+..#..
+.....
+..##.
+.....
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(25));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(4));
+    }
 }
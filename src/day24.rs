@@ -1,81 +1,34 @@
-use jungle::readfile;
-
-#[derive(Copy, Clone, Debug)]
-struct Blizzard {
-    start: usize,
-}
+use jungle::map::Map;
+use jungle::{Answer, Contents, Timed};
 
+// The valley floor wraps around, a blizzard leaving one edge re-appears at the opposite edge
 struct Valley {
     width: usize,
     height: usize,
-    up: Vec<Vec<Blizzard>>,
-    dn: Vec<Vec<Blizzard>>,
-    lt: Vec<Vec<Blizzard>>,
-    rt: Vec<Vec<Blizzard>>,
+    blizzards: Map<u8>,
 }
 
 impl Valley {
     fn new(width: usize, height: usize) -> Self {
-        let mut up = Vec::new();
-        let mut dn = Vec::new();
-        for _ in 0..width {
-            up.push(Vec::new());
-            dn.push(Vec::new());
-        }
-        let mut lt = Vec::new();
-        let mut rt = Vec::new();
-        for _ in 0..width {
-            lt.push(Vec::new());
-            rt.push(Vec::new());
-        }
+        let corner = (width as isize - 1, height as isize - 1);
         Self {
             width,
             height,
-            up,
-            dn,
-            lt,
-            rt,
+            blizzards: Map::torus((0, 0), corner),
         }
     }
 
-    fn add_up(&mut self, col: usize, addition: Blizzard) {
-        self.up[col].push(addition);
-    }
-
-    fn add_dn(&mut self, col: usize, addition: Blizzard) {
-        self.dn[col].push(addition);
-    }
-
-    fn add_lt(&mut self, row: usize, addition: Blizzard) {
-        self.lt[row].push(addition);
-    }
-
-    fn add_rt(&mut self, row: usize, addition: Blizzard) {
-        self.rt[row].push(addition);
+    fn add(&mut self, row: usize, col: usize, blizzard: u8) {
+        self.blizzards.write(col as isize, row as isize, blizzard);
     }
 
+    // Safe unless a blizzard which started tick spaces away is heading here
     fn safe(&self, tick: usize, row: usize, col: usize) -> bool {
-        for b in self.up[col].iter() {
-            if (row + tick) % self.height == b.start {
-                return false;
-            }
-        }
-        for b in self.dn[col].iter() {
-            if (b.start + tick) % self.height == row {
-                return false;
-            }
-        }
-        for b in self.lt[row].iter() {
-            if (col + tick) % self.width == b.start {
-                return false;
-            }
-        }
-        for b in self.rt[row].iter() {
-            if (b.start + tick) % self.width == col {
-                return false;
-            }
-        }
-        true
+        let (row, col, tick) = (row as isize, col as isize, tick as isize);
+        self.blizzards.read(col, row + tick) != Some(b'^')
+            && self.blizzards.read(col, row - tick) != Some(b'v')
+            && self.blizzards.read(col + tick, row) != Some(b'<')
+            && self.blizzards.read(col - tick, row) != Some(b'>')
     }
 }
 
@@ -85,22 +38,25 @@ struct Expedition {
     col: usize,
 }
 
-use std::collections::HashSet;
-
 impl Expedition {
+    // Outside the valley, at the entrance
     fn new() -> Self {
         Self { row: 0, col: 1 }
     }
 
-    fn at_exit(&self, valley: &Valley) -> bool {
-        self.row == valley.height && self.col == valley.width
-    }
-
-    fn at_entrance(&self, _valley: &Valley) -> bool {
-        self.row == 1 && self.col == 1
+    // Outside the valley, having left by the exit
+    fn exit(valley: &Valley) -> Self {
+        Self {
+            row: valley.height + 1,
+            col: valley.width,
+        }
     }
 
     fn adapt(valley: &Valley, tick: usize, row: usize, col: usize) -> bool {
+        let outside = Self { row, col };
+        if outside == Self::new() || outside == Self::exit(valley) {
+            return true;
+        }
         if row == 0 || col == 0 || row > valley.height || col > valley.width {
             return false;
         }
@@ -108,182 +64,53 @@ impl Expedition {
         valley.safe(tick, row - 1, col - 1)
     }
 
-    // Simulate valley in this tick and identify adjacent safe spaces
-    fn next(self, tick: usize, valley: &Valley) -> Vec<Self> {
-        let mut v = Vec::new();
-        // At the start...
-        if self.row == 0 {
-            if Expedition::adapt(valley, tick, self.row + 1, self.col) {
-                v.push(Self {
-                    row: self.row + 1,
-                    col: self.col,
-                });
-            }
-            // Remain outside waiting
-            v.push(self);
-            return v;
-        }
-
-        // At the end... (coming back?)
-        if self.row == valley.height + 1 {
-            if Expedition::adapt(valley, tick, self.row - 1, self.col) {
-                v.push(Self {
-                    row: self.row - 1,
-                    col: self.col,
-                });
-            }
-            // Remain outside waiting
-            v.push(self);
-            return v;
-        }
-
-        // In the valley, dodging blizzards
-        // Wait?
-        if Expedition::adapt(valley, tick, self.row, self.col) {
-            v.push(self);
-        }
-
-        // Up
-        if Expedition::adapt(valley, tick, self.row - 1, self.col) {
-            v.push(Self {
-                row: self.row - 1,
-                col: self.col,
-            });
-        }
-
-        // Left
-        if Expedition::adapt(valley, tick, self.row, self.col - 1) {
-            v.push(Self {
-                row: self.row,
-                col: self.col - 1,
-            });
-        }
-
-        // Down
-        if Expedition::adapt(valley, tick, self.row + 1, self.col) {
-            v.push(Self {
-                row: self.row + 1,
-                col: self.col,
-            });
-        }
-
-        // Right
-        if Expedition::adapt(valley, tick, self.row, self.col + 1) {
-            v.push(Self {
-                row: self.row,
-                col: self.col + 1,
-            });
-        }
-
-        v
-    }
-
     fn route(valley: &Valley) -> usize {
-        let mut current: Vec<Expedition> = Vec::new();
-        let start = Expedition::new();
-        current.push(start);
-        let mut tick = 0;
-        loop {
-            let mut next: Vec<Expedition> = Vec::new();
-            let mut seen: HashSet<Expedition> = HashSet::new();
-
-            tick += 1;
-            for c in current {
-                for x in c.next(tick, valley) {
-                    if !seen.contains(&x) {
-                        seen.insert(x);
-                        next.push(x);
-                        if x.at_exit(valley) {
-                            // It takes one minute to actually leave the valley
-                            return tick + 1;
-                        }
-                    }
-                }
-            }
-            current = next;
-        }
+        let exit = Expedition::exit(valley);
+        Expedition::best(Expedition::new(), 0, exit, valley).expect("There should be a way out")
     }
 
     // There, and back again, and there again
     fn hobbit(valley: &Valley) -> usize {
-        let mut current: Vec<Expedition> = Vec::new();
-        let start = Expedition::new();
-        current.push(start);
-        let mut tick = 0;
-        'first: loop {
-            let mut next: Vec<Expedition> = Vec::new();
-            let mut seen: HashSet<Expedition> = HashSet::new();
-
-            tick += 1;
-            for c in current {
-                for x in c.next(tick, valley) {
-                    if !seen.contains(&x) {
-                        seen.insert(x);
-                        next.push(x);
-                        if x.at_exit(valley) {
-                            // It takes one minute to actually leave the valley
-                            tick += 1;
-                            break 'first;
-                        }
-                    }
-                }
-            }
-            current = next;
-        }
-
-        let end = Expedition {
-            row: valley.height + 1,
-            col: valley.width,
-        };
-        current = vec![end];
-        'second: loop {
-            let mut next: Vec<Expedition> = Vec::new();
-            let mut seen: HashSet<Expedition> = HashSet::new();
-
-            tick += 1;
-            for c in current {
-                for x in c.next(tick, valley) {
-                    if !seen.contains(&x) {
-                        seen.insert(x);
-                        next.push(x);
-                        if x.at_entrance(valley) {
-                            // It takes one minute to actually leave the valley
-                            tick += 1;
-                            break 'second;
-                        }
-                    }
-                }
-            }
-            current = next;
-        }
-
-        current = Vec::new();
-        current.push(start);
-        loop {
-            let mut next: Vec<Expedition> = Vec::new();
-            let mut seen: HashSet<Expedition> = HashSet::new();
+        let (start, end) = (Expedition::new(), Expedition::exit(valley));
+        let there = |e: &Expedition| *e == end;
+        let back = |e: &Expedition| *e == start;
+        let legs = Expedition::waypoints(start, 0, &[&there, &back, &there], valley)
+            .expect("There should be a way through");
+        legs.total()
+    }
+}
 
-            tick += 1;
-            for c in current {
-                for x in c.next(tick, valley) {
-                    if !seen.contains(&x) {
-                        seen.insert(x);
-                        next.push(x);
-                        if x.at_exit(valley) {
-                            // It takes one minute to actually leave the valley
-                            return tick + 1;
-                        }
-                    }
-                }
-            }
+impl Timed<Valley> for Expedition {
+    // Simulate valley in this tick and identify adjacent safe spaces
+    fn next(&self, tick: usize, valley: &Valley) -> Vec<Self> {
+        let (row, col) = (self.row, self.col);
+        // Wait, Up, Left, Down, Right
+        let moves = [
+            Some((row, col)),
+            row.checked_sub(1).map(|row| (row, col)),
+            col.checked_sub(1).map(|col| (row, col)),
+            Some((row + 1, col)),
+            Some((row, col + 1)),
+        ];
+        moves
+            .into_iter()
+            .flatten()
+            .filter(|&(row, col)| Expedition::adapt(valley, tick, row, col))
+            .map(|(row, col)| Self { row, col })
+            .collect()
+    }
 
-            current = next;
+    // The blizzards are all back where they started after this many ticks
+    fn period(valley: &Valley) -> Option<usize> {
+        let (mut a, mut b) = (valley.width, valley.height);
+        while b != 0 {
+            (a, b) = (b, a % b);
         }
+        Some(valley.width * valley.height / a)
     }
 }
 
-fn read_map(filename: &str) -> Valley {
-    let ctxt = readfile(filename);
+fn read_map(ctxt: &Contents) -> Valley {
     let height = ctxt.lines().count() - 2;
     let width = ctxt.lines().next().unwrap().len() - 2;
     let mut valley = Valley::new(width, height);
@@ -297,17 +124,8 @@ fn read_map(filename: &str) -> Valley {
                     }
                 }
                 b'.' => { /* empty, do nothing */ }
-                b'<' => {
-                    valley.add_lt(row - 1, Blizzard { start: col - 1 });
-                }
-                b'>' => {
-                    valley.add_rt(row - 1, Blizzard { start: col - 1 });
-                }
-                b'^' => {
-                    valley.add_up(col - 1, Blizzard { start: row - 1 });
-                }
-                b'v' => {
-                    valley.add_dn(col - 1, Blizzard { start: row - 1 });
+                b'<' | b'>' | b'^' | b'v' => {
+                    valley.add(row - 1, col - 1, byte);
                 }
                 _ => {
                     panic!("Map input should only mark blizzards, etc.");
@@ -318,14 +136,40 @@ fn read_map(filename: &str) -> Valley {
     valley
 }
 
-pub fn a() {
-    let map: Valley = read_map("24");
+pub fn a(ctxt: &Contents) -> Answer {
+    let map: Valley = read_map(ctxt);
     let ticks = Expedition::route(&map);
-    println!("Expedition took {ticks} minutes to cross valley");
+    ticks.into()
 }
 
-pub fn b() {
-    let map: Valley = read_map("24");
+pub fn b(ctxt: &Contents) -> Answer {
+    let map: Valley = read_map(ctxt);
     let ticks = Expedition::hobbit(&map);
-    println!("I took {ticks} minutes to cross valley, go back for snacks, and return");
+    ticks.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day24::{a, b};
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::Number(18));
+    }
+
+    #[test]
+    fn example_b() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(b(&ctxt), Answer::Number(54));
+    }
 }
@@ -1,4 +1,4 @@
-use jungle::readfile;
+use jungle::{Answer, Contents};
 
 use core::ops::Add;
 use core::ops::AddAssign;
@@ -81,12 +81,38 @@ impl fmt::Display for Five {
     }
 }
 
-pub fn a() {
-    let ctxt = readfile("25");
+pub fn a(ctxt: &Contents) -> Answer {
     let sum: Five = ctxt.lines().map(|l| l.parse().unwrap()).sum();
-    println!("{sum}");
+    sum.to_string().into()
 }
 
-pub fn b() {
-    println!("Happy Christmas!");
+pub fn b(_ctxt: &Contents) -> Answer {
+    "Happy Christmas!".into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day25::a;
+    use jungle::{Answer, Contents};
+
+    const EXAMPLE: &str = "1=-0-2
+12111
+2=0=
+21
+2=01
+111
+20012
+112
+1=-1=
+1-12
+12
+1=
+122
+";
+
+    #[test]
+    fn example_a() {
+        let ctxt = Contents::from(EXAMPLE);
+        assert_eq!(a(&ctxt), Answer::from("2=-1=0"));
+    }
 }
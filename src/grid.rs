@@ -0,0 +1,248 @@
+//! N dimensional counterpart to Map, e.g. for voxels
+//!
+//! ```
+//! use jungle::grid::Grid;
+//!
+//! let mut grid: Grid<bool, 3> = Grid::new();
+//! grid.write([1, 2, 3], true);
+//! grid.write([1, 2, 4], true);
+//! let touching = grid.neighbours([1, 2, 3]).filter(|&(_, b)| b == Some(true));
+//! assert_eq!(touching.count(), 1);
+//! ```
+
+use crate::map::Plane;
+use std::ops::RangeInclusive;
+
+/// A type for N dimensional grids of unknown expanses, the backing store automatically grows as
+/// necessary, positions are arrays of N co-ordinates
+#[derive(Clone)]
+pub struct Grid<T: Copy + Default, const N: usize> {
+    data: Vec<T>,
+    planes: [Plane; N],
+}
+
+impl<T: Copy + Default, const N: usize> Default for Grid<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Number of allocated positions for these planes
+fn volume<const N: usize>(planes: &[Plane; N]) -> usize {
+    planes.iter().map(|p| p.size as usize).product()
+}
+
+impl<T: Copy + Default, const N: usize> Grid<T, N> {
+    /// Grid initially allocated from one corner to the other but it will grow automatically as
+    /// necessary, the live area is empty until the first write
+    pub fn rect(from: [isize; N], to: [isize; N]) -> Self {
+        let planes: [Plane; N] = std::array::from_fn(|k| Plane::vacant(from[k], to[k]));
+        let data = vec![T::default(); volume(&planes)];
+        Self { data, planes }
+    }
+
+    pub fn new() -> Self {
+        Self::rect([-8; N], [8; N])
+    }
+
+    fn inbound(&self, posn: [isize; N]) -> bool {
+        self.planes
+            .iter()
+            .zip(posn)
+            .all(|(p, n)| n >= p.offset && n < p.offset + p.size)
+    }
+
+    fn live(&self, posn: [isize; N]) -> bool {
+        self.planes
+            .iter()
+            .zip(posn)
+            .all(|(p, n)| (p.start..=p.end).contains(&n))
+    }
+
+    // Index of posn in data allocated for these planes, the first co-ordinate varies fastest
+    fn position(planes: &[Plane; N], posn: [isize; N]) -> usize {
+        let mut index = 0;
+        for (p, n) in planes.iter().zip(posn).rev() {
+            index = index * p.size + (n - p.offset);
+        }
+        index as usize
+    }
+
+    fn noitisop(&self, mut i: usize) -> [isize; N] {
+        std::array::from_fn(|k| {
+            let p = &self.planes[k];
+            let n = p.offset + (i % p.size as usize) as isize;
+            i /= p.size as usize;
+            n
+        })
+    }
+
+    /// Grow Grid by suitably expanding every plane and re-allocating, then copying
+    fn grow(&mut self) {
+        let planes = self.planes.map(|p| p.expand());
+        let mut data = vec![T::default(); volume(&planes)];
+        for (i, &value) in self.data.iter().enumerate() {
+            data[Self::position(&planes, self.noitisop(i))] = value;
+        }
+        self.data = data;
+        self.planes = planes;
+    }
+
+    fn include(&mut self, posn: [isize; N]) {
+        for (p, n) in self.planes.iter_mut().zip(posn) {
+            p.start = p.start.min(n);
+            p.end = p.end.max(n);
+        }
+        if !self.inbound(posn) {
+            self.grow();
+        }
+    }
+
+    /// Live range of values along an axis, where axis 0 is the first co-ordinate
+    pub fn axis(&self, axis: usize) -> RangeInclusive<isize> {
+        self.planes[axis].start..=self.planes[axis].end
+    }
+
+    /// Write to posn in the Grid, this will grow the grid automatically
+    pub fn write(&mut self, posn: [isize; N], value: T) {
+        self.include(posn);
+        let index = Self::position(&self.planes, posn);
+        self.data[index] = value;
+    }
+
+    /// Reads a position on the Grid, but can be None if that position wasn't yet mapped
+    pub fn read(&self, posn: [isize; N]) -> Option<T> {
+        self.inbound(posn)
+            .then(|| self.data[Self::position(&self.planes, posn)])
+    }
+
+    /// Count how many of the mapped positions in the live area match the predicate
+    pub fn count<P>(&self, mut predicate: P) -> usize
+    where
+        P: FnMut(&&T) -> bool,
+    {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|&(i, value)| self.live(self.noitisop(i)) && predicate(&value))
+            .count()
+    }
+
+    /// Obtain a Vec of positions in the live area matching the predicate, ordered by the last
+    /// co-ordinate, then the one before and so on, like a Map's rows then columns
+    pub fn find<P>(&self, predicate: P) -> Vec<[isize; N]>
+    where
+        P: Fn(T) -> bool,
+    {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|&(i, &value)| self.live(self.noitisop(i)) && predicate(value))
+            .map(|(i, _)| self.noitisop(i))
+            .collect()
+    }
+
+    // Value at posn, but spare capacity outside the live area counts as unmapped
+    fn lookup(&self, posn: [isize; N]) -> Option<T> {
+        self.live(posn).then(|| self.read(posn)).flatten()
+    }
+
+    /// The 2N positions which share a face with posn, six in 3D, with their values or None if
+    /// they weren't mapped
+    pub fn neighbours(
+        &self,
+        posn: [isize; N],
+    ) -> impl Iterator<Item = ([isize; N], Option<T>)> + '_ {
+        (0..N).flat_map(move |k| {
+            [-1, 1].map(|d| {
+                let mut next = posn;
+                next[k] += d;
+                (next, self.lookup(next))
+            })
+        })
+    }
+
+    /// The 3^N - 1 positions which touch posn at all, twenty six in 3D, with their values or
+    /// None if they weren't mapped
+    pub fn surrounding(
+        &self,
+        posn: [isize; N],
+    ) -> impl Iterator<Item = ([isize; N], Option<T>)> + '_ {
+        (0..3usize.pow(N as u32))
+            .map(move |mut i| {
+                std::array::from_fn(|k| {
+                    let d = (i % 3) as isize - 1;
+                    i /= 3;
+                    posn[k] + d
+                })
+            })
+            .filter(move |&next| next != posn)
+            .map(|next| (next, self.lookup(next)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::Grid;
+
+    #[test]
+    fn grow() {
+        let mut grid: Grid<u8, 3> = Grid::rect([0, 0, 0], [2, 2, 2]);
+        grid.write([1, 1, 1], 5);
+        grid.write([-20, 3, 40], 7);
+        grid.write([30, -3, 0], 9);
+        assert_eq!(grid.read([1, 1, 1]), Some(5));
+        assert_eq!(grid.read([-20, 3, 40]), Some(7));
+        assert_eq!(grid.read([30, -3, 0]), Some(9));
+        assert_eq!(grid.read([500, 0, 0]), None);
+        assert_eq!(grid.count(|&&n| n > 0), 3);
+        assert_eq!(grid.count(|_| true), 51 * 7 * 41);
+        assert_eq!(grid.find(|n| n > 0), [[30, -3, 0], [1, 1, 1], [-20, 3, 40]]);
+        assert_eq!(grid.axis(0), -20..=30);
+        assert_eq!(grid.axis(2), 0..=40);
+    }
+
+    #[test]
+    fn vacant() {
+        let mut grid: Grid<u8, 3> = Grid::new();
+        assert_eq!(grid.count(|_| true), 0);
+        assert!(grid.axis(0).is_empty());
+        grid.write([10, 20, 30], 1);
+        assert_eq!(grid.count(|_| true), 1);
+        assert_eq!(grid.axis(1), 20..=20);
+        assert_eq!(grid.find(|_| true), [[10, 20, 30]]);
+    }
+
+    #[test]
+    fn neighbours() {
+        let mut grid: Grid<bool, 3> = Grid::new();
+        grid.write([0, 0, 0], true);
+        grid.write([2, 2, 2], true);
+        assert_eq!(grid.neighbours([1, 1, 1]).count(), 6);
+        assert_eq!(grid.surrounding([1, 1, 1]).count(), 26);
+        let on = grid
+            .surrounding([1, 1, 1])
+            .filter(|&(_, b)| b == Some(true));
+        assert_eq!(
+            on.map(|(posn, _)| posn).collect::<Vec<_>>(),
+            [[0, 0, 0], [2, 2, 2]]
+        );
+        assert_eq!(
+            grid.neighbours([1, 1, 1])
+                .filter(|&(_, b)| b == Some(true))
+                .count(),
+            0
+        );
+        assert_eq!(
+            grid.neighbours([2, 2, 2])
+                .filter(|(_, b)| b.is_none())
+                .count(),
+            3
+        );
+
+        let square: Grid<u8, 2> = Grid::new();
+        assert_eq!(square.surrounding([0, 0]).count(), 8);
+        let four: Vec<_> = square.neighbours([5, 5]).map(|(posn, _)| posn).collect();
+        assert_eq!(four, [[4, 5], [6, 5], [5, 4], [5, 6]]);
+    }
+}
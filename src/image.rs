@@ -0,0 +1,250 @@
+//! Pictures of maps, as PPM or PNG files, and numbered sequences of them to watch a simulation
+//!
+//! ```
+//! use jungle::map::Map;
+//!
+//! let map: Map<char> = "#.\n.#\n".parse().unwrap();
+//! let image = map.image(|ch| if ch == '#' { [0, 0, 0] } else { [255, 255, 255] });
+//! assert_eq!((image.width(), image.height()), (2, 2));
+//! assert!(image.ppm().starts_with(b"P6\n2 2\n255\n"));
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Red, green and blue
+pub type Rgb = [u8; 3];
+
+/// A rectangle of pixels, row by row
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+/// File formats an Image can be written in
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Binary portable pixmap, simple but large
+    Ppm,
+    /// Portable Network Graphics, uncompressed here but every viewer understands them
+    Png,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ppm => "ppm",
+            Format::Png => "png",
+        }
+    }
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, pixels: Vec<Rgb>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "An Image needs exactly one pixel per position"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The pixel in this column of this row, counting from the top left
+    pub fn pixel(&self, col: usize, row: usize) -> Rgb {
+        self.pixels[row * self.width + col]
+    }
+
+    /// Binary PPM (P6) file contents
+    pub fn ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        out.extend(self.pixels.iter().flatten());
+        out
+    }
+
+    /// PNG file contents, the pixels are stored rather than compressed
+    pub fn png(&self) -> Vec<u8> {
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+
+        let mut header = Vec::new();
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, default compression, filtering and no interlace
+        header.extend([8, 2, 0, 0, 0]);
+        chunk(&mut out, b"IHDR", &header);
+
+        let mut raw = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in 0..self.height {
+            raw.push(0); // No filter
+            let pixels = &self.pixels[row * self.width..(row + 1) * self.width];
+            raw.extend(pixels.iter().flatten());
+        }
+        chunk(&mut out, b"IDAT", &zlib(&raw));
+
+        chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    /// Contents of the file in this format
+    pub fn encode(&self, format: Format) -> Vec<u8> {
+        match format {
+            Format::Ppm => self.ppm(),
+            Format::Png => self.png(),
+        }
+    }
+
+    /// Write an image file in this format
+    pub fn save(&self, path: impl AsRef<Path>, format: Format) -> io::Result<()> {
+        fs::write(path, self.encode(format))
+    }
+}
+
+// Append a PNG chunk with its length and checksum
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+// zlib stream of stored (uncompressed) deflate blocks
+fn zlib(data: &[u8]) -> Vec<u8> {
+    const BLOCK: usize = 65535;
+
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        out.push(last as u8);
+        let len = block.len() as u16;
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % MOD;
+        b = (b + a) % MOD;
+    }
+    (b << 16) | a
+}
+
+/// Writes a numbered sequence of images into a directory, e.g. one each step of a simulation,
+/// to be inspected or assembled into an animation with other tools
+pub struct Frames {
+    dir: PathBuf,
+    format: Format,
+    next: usize,
+}
+
+impl Frames {
+    /// Frames in this directory, which is created if necessary
+    pub fn new(dir: impl Into<PathBuf>, format: Format) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            format,
+            next: 0,
+        })
+    }
+
+    /// Write the next frame, returning where it was written
+    pub fn write(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let name = format!("frame{:05}.{}", self.next, self.format.extension());
+        let path = self.dir.join(name);
+        image.save(&path, self.format)?;
+        self.next += 1;
+        Ok(path)
+    }
+
+    /// How many frames were written so far
+    pub fn count(&self) -> usize {
+        self.next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::image::{adler32, crc32, Format, Frames, Image};
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn encode() {
+        let image = Image::new(2, 1, vec![[255, 0, 0], [0, 0, 255]]);
+        assert_eq!(image.ppm(), b"P6\n2 1\n255\n\xff\0\0\0\0\xff");
+
+        let png = image.png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x02\0\0\0\x01"));
+        assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+        // Signature, then IHDR, IDAT holding 7 bytes of pixels, and IEND chunks
+        assert_eq!(png.len(), 8 + (12 + 13) + (12 + 2 + 5 + 7 + 4) + 12);
+
+        let big = Image::new(300, 300, vec![[1, 2, 3]; 90000]);
+        assert_eq!(big.pixel(299, 299), [1, 2, 3]);
+        let png = big.png();
+        // Too many pixels for one stored block, so five blocks each with their own header
+        let raw = 300 * (300 * 3 + 1);
+        assert_eq!(png.len(), 8 + 25 + 12 + 2 + 5 * 5 + raw + 4 + 12);
+    }
+
+    #[test]
+    fn frames() {
+        let dir = std::env::temp_dir().join(format!("jungle-frames-{}", std::process::id()));
+        let mut frames = Frames::new(&dir, Format::Ppm).unwrap();
+        let image = Image::new(1, 1, vec![[9, 9, 9]]);
+        frames.write(&image).unwrap();
+        let path = frames.write(&image).unwrap();
+        assert_eq!(frames.count(), 2);
+        assert_eq!(path, dir.join("frame00001.ppm"));
+        assert_eq!(std::fs::read(&path).unwrap(), image.ppm());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
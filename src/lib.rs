@@ -1,47 +1,92 @@
+/// Build a Registry of the named day modules, each of which has a() and b() functions taking
+/// &Contents and returning an Answer, the day number comes from the module name e.g. day04
 #[macro_export]
 macro_rules! days {
-    ($obj:expr, $($day:ident,)+) => {
-        days!($obj, $(days!($day a) => $day::a(), days!($day b) => $day::b()),*, _ => { println!("{} not available yet", $obj); });
-    };
-    ($obj:expr, $($day:ident),+) => {
-        days!($obj, $($day,)+);
-    };
-    ($s:ident a) => {
-        concat!(stringify!($s), 'a')
-    };
-    ($s:ident b) => {
-        concat!(stringify!($s), 'b')
-    };
-    ($obj:expr, $($matcher:pat => $result:expr),*) => {
-       match $obj {
-           $($matcher => $result),*
-       }
-    }
+    ($($day:ident),+ $(,)?) => {{
+        let mut registry = $crate::Registry::new();
+        $(
+            let day: u8 = stringify!($day)
+                .trim_start_matches("day")
+                .parse()
+                .expect("Day modules should be named like day04");
+            registry.add($crate::Day::new(day, $day::a, $day::b));
+        )+
+        registry
+    }};
 }
 
 pub struct Contents {
     pub text: String,
 }
 
+impl From<&str> for Contents {
+    fn from(text: &str) -> Self {
+        Contents {
+            text: text.to_string(),
+        }
+    }
+}
+
+/// A problem found in the Contents, with the 1-based line and column where the offending text
+/// starts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+use std::fmt;
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!(
+            "line {}, column {}: {} in {:?}",
+            self.line, self.column, self.reason, self.text
+        ))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 impl<'t> Contents {
-    pub fn lines(&'t self) -> impl Iterator<Item = &str> + 't {
+    pub fn lines(&'t self) -> impl Iterator<Item = &'t str> + 't {
         self.text.lines()
     }
 
+    /// Each line as a number, lines which aren't numbers are 0, see try_numbers
     pub fn numbers(&self) -> impl Iterator<Item = isize> + '_ {
         self.lines().map(|l| l.parse::<isize>().unwrap_or(0))
     }
 
+    pub fn try_numbers(&self) -> impl Iterator<Item = Result<isize, ParseError>> + '_ {
+        self.lines().map(|l| self.parse(l))
+    }
+
+    /// Each line as a binary number, lines which aren't binary are skipped, see try_binary
     pub fn binary(&self) -> impl Iterator<Item = isize> + '_ {
         self.lines()
             .filter_map(|l| isize::from_str_radix(l, 2).ok())
     }
 
+    pub fn try_binary(&self) -> impl Iterator<Item = Result<isize, ParseError>> + '_ {
+        self.lines()
+            .map(|l| isize::from_str_radix(l, 2).map_err(|e| self.error(l, e)))
+    }
+
     pub fn digits(&self) -> impl Iterator<Item = u32> + '_ {
         self.text.trim().chars().map(|c| c.to_digit(10).unwrap())
     }
 
-    pub fn list(&'t self) -> impl Iterator<Item = &str> + 't {
+    pub fn try_digits(&self) -> impl Iterator<Item = Result<u32, ParseError>> + '_ {
+        let value = self.value();
+        value.char_indices().map(move |(k, c)| {
+            c.to_digit(10)
+                .ok_or_else(|| self.error(&value[k..k + c.len_utf8()], "not a decimal digit"))
+        })
+    }
+
+    pub fn list(&'t self) -> impl Iterator<Item = &'t str> + 't {
         self.text.trim().split(',')
     }
 
@@ -49,6 +94,10 @@ impl<'t> Contents {
         self.list().map(|n| n.parse().unwrap())
     }
 
+    pub fn try_list_numbers(&self) -> impl Iterator<Item = Result<isize, ParseError>> + '_ {
+        self.list().map(|n| self.parse(n))
+    }
+
     pub fn value(&self) -> &str {
         self.text.trim()
     }
@@ -56,19 +105,357 @@ impl<'t> Contents {
     pub fn number(&self) -> isize {
         self.value().parse().unwrap()
     }
+
+    pub fn try_number(&self) -> Result<isize, ParseError> {
+        self.parse(self.value())
+    }
+
+    /// Parse some part of the text, e.g. from lines(), reporting where it was if that fails
+    pub fn parse<F>(&self, part: &str) -> Result<F, ParseError>
+    where
+        F: std::str::FromStr,
+        F::Err: fmt::Display,
+    {
+        part.parse().map_err(|e| self.error(part, e))
+    }
+
+    /// ParseError for some part of the text, the part must be a slice of our text
+    pub fn error(&self, part: &str, reason: impl fmt::Display) -> ParseError {
+        let (line, column) = self.locate(part);
+        ParseError {
+            line,
+            column,
+            text: part.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Line and column (both counting from 1) where this part of the text starts
+    pub fn locate(&self, part: &str) -> (usize, usize) {
+        let offset = (part.as_ptr() as usize)
+            .checked_sub(self.text.as_ptr() as usize)
+            .filter(|&offset| offset <= self.text.len())
+            .expect("Only parts of the Contents can be located");
+        let before = &self.text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let start = before.rfind('\n').map_or(0, |n| n + 1);
+        let column = before[start..].chars().count() + 1;
+        (line, column)
+    }
+}
+
+/// The file could not be read at all
+#[derive(Debug)]
+pub struct ReadError {
+    pub filename: String,
+    pub source: std::io::Error,
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!(
+            "Unable to read file {}: {}",
+            self.filename, self.source
+        ))
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
 }
 
 use std::fs;
 
-pub fn readfile(filename: &str) -> Contents {
-    let text = fs::read_to_string(filename).expect("Unable to read file");
-    Contents { text }
+pub fn readfile(filename: &str) -> Result<Contents, ReadError> {
+    match fs::read_to_string(filename) {
+        Ok(text) => Ok(Contents { text }),
+        Err(source) => Err(ReadError {
+            filename: filename.to_string(),
+            source,
+        }),
+    }
+}
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+// Standard input can only be read once, so keep it for every day which wants it
+fn stdin() -> std::io::Result<String> {
+    static STDIN: OnceLock<String> = OnceLock::new();
+
+    if let Some(text) = STDIN.get() {
+        return Ok(text.clone());
+    }
+    let text = std::io::read_to_string(std::io::stdin())?;
+    Ok(STDIN.get_or_init(|| text).clone())
+}
+
+/// Where to find the input for each day
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Input {
+    /// Inputs in this directory named for the day, e.g. 04 for the real input set, and 04.example
+    /// for the example set
+    Dir { dir: PathBuf, set: String },
+    /// This one file regardless of the day
+    File(PathBuf),
+    /// Read standard input, regardless of the day
+    Stdin,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Input::Dir {
+            dir: Input::default_dir(),
+            set: Input::REAL.to_string(),
+        }
+    }
+}
+
+impl Input {
+    /// Environment variable naming the input directory
+    pub const ENV: &'static str = "JUNGLE_INPUT";
+    /// The input set whose files have the bare day number as a name
+    pub const REAL: &'static str = "real";
+
+    /// Directory named by the environment, or else the current directory
+    pub fn default_dir() -> PathBuf {
+        std::env::var_os(Input::ENV)
+            .unwrap_or_else(|| ".".into())
+            .into()
+    }
+
+    /// A file name, or - for standard input
+    pub fn file(name: &str) -> Self {
+        if name == "-" {
+            Input::Stdin
+        } else {
+            Input::File(name.into())
+        }
+    }
+
+    /// Name of the input set, for a single file this is just the file name
+    pub fn set(&self) -> String {
+        match self {
+            Input::Dir { set, .. } => set.clone(),
+            Input::File(path) => path.display().to_string(),
+            Input::Stdin => "-".to_string(),
+        }
+    }
+
+    /// Path for this day's input, None for standard input
+    pub fn path(&self, day: u8) -> Option<PathBuf> {
+        match self {
+            Input::Dir { dir, set } if set == Input::REAL => Some(dir.join(format!("{day:02}"))),
+            Input::Dir { dir, set } => Some(dir.join(format!("{day:02}.{set}"))),
+            Input::File(path) => Some(path.clone()),
+            Input::Stdin => None,
+        }
+    }
+
+    /// Contents of this day's input, standard input is only read once and then every day gets
+    /// the same Contents
+    pub fn read(&self, day: u8) -> Result<Contents, ReadError> {
+        match self.path(day) {
+            Some(path) => readfile(&path.to_string_lossy()),
+            None => match stdin() {
+                Ok(text) => Ok(Contents { text }),
+                Err(source) => Err(ReadError {
+                    filename: "standard input".to_string(),
+                    source,
+                }),
+            },
+        }
+    }
+}
+
+/// An answer to one part of a day's puzzle
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+    /// Several lines of text which together show the answer
+    Picture(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => f.write_fmt(format_args!("{n}")),
+            Answer::Text(s) | Answer::Picture(s) => f.write_str(s),
+        }
+    }
+}
+
+macro_rules! number_answer {
+    ($($t:ty),+) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Number(i64::try_from(n).expect("Answer should fit in an i64"))
+                }
+            }
+        )+
+    };
+}
+
+number_answer!(u8, u16, u32, u64, usize, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// Each day's puzzle has two parts, A and B
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::A, Part::B];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Part::A => "a",
+            Part::B => "b",
+        })
+    }
+}
+
+impl std::str::FromStr for Part {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a" | "A" => Ok(Part::A),
+            "b" | "B" => Ok(Part::B),
+            _ => Err("Part should be a or b"),
+        }
+    }
+}
+
+/// Solves both parts of one day's puzzle from that day's input
+pub trait Solver {
+    fn day(&self) -> u8;
+    fn a(&self, ctxt: &Contents) -> Answer;
+    fn b(&self, ctxt: &Contents) -> Answer;
+
+    fn solve(&self, part: Part, ctxt: &Contents) -> Answer {
+        match part {
+            Part::A => self.a(ctxt),
+            Part::B => self.b(ctxt),
+        }
+    }
+}
+
+/// A Solver made from a pair of functions, which is how the days! macro builds them
+pub struct Day {
+    day: u8,
+    a: fn(&Contents) -> Answer,
+    b: fn(&Contents) -> Answer,
+}
+
+impl Day {
+    pub fn new(day: u8, a: fn(&Contents) -> Answer, b: fn(&Contents) -> Answer) -> Self {
+        Self { day, a, b }
+    }
+}
+
+impl Solver for Day {
+    fn day(&self) -> u8 {
+        self.day
+    }
+
+    fn a(&self, ctxt: &Contents) -> Answer {
+        (self.a)(ctxt)
+    }
+
+    fn b(&self, ctxt: &Contents) -> Answer {
+        (self.b)(ctxt)
+    }
+}
+
+/// Every available Solver, in order by day
+///
+/// The library registers no days itself, because the day modules are the binary's puzzle
+/// solutions built on this library, and a library cannot depend on its own binary. Whichever
+/// program has the solutions builds its Registry, as main does with days!, so a bench which
+/// wants them declares the day modules itself, e.g. with #[path]
+#[derive(Default)]
+pub struct Registry {
+    solvers: Vec<Box<dyn Solver>>,
 }
 
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a Solver, replacing any existing Solver for the same day
+    pub fn add(&mut self, solver: impl Solver + 'static) {
+        let day = solver.day();
+        match self.solvers.binary_search_by_key(&day, |s| s.day()) {
+            Ok(k) => self.solvers[k] = Box::new(solver),
+            Err(k) => self.solvers.insert(k, Box::new(solver)),
+        }
+    }
+
+    pub fn get(&self, day: u8) -> Option<&dyn Solver> {
+        self.solvers
+            .binary_search_by_key(&day, |s| s.day())
+            .ok()
+            .map(|k| self.solvers[k].as_ref())
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = &dyn Solver> + '_ {
+        self.solvers.iter().map(|s| s.as_ref())
+    }
+
+    /// Every (day, part) which can be solved
+    pub fn parts(&self) -> impl Iterator<Item = (u8, Part)> + '_ {
+        self.days()
+            .flat_map(|s| Part::BOTH.into_iter().map(move |p| (s.day(), p)))
+    }
+}
+
+use core::cmp::Reverse;
 use core::ops::ControlFlow;
 use std::collections::hash_map;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Function giving the States one transition away from a State
+pub type Transition<'f, S, T> = &'f dyn Fn(&S, &T) -> Vec<S>;
+
+/// Predicate for a State which is one of the waypoints on a route
+pub type Goal<'f, S> = &'f dyn Fn(&S) -> bool;
+
+/// The Steps taken for each leg of a route through waypoints, and the State at the end
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Legs<S> {
+    pub steps: Vec<usize>,
+    pub end: S,
+}
+
+impl<S> Legs<S> {
+    /// Steps taken for the whole route
+    pub fn total(&self) -> usize {
+        self.steps.iter().sum()
+    }
+}
 
 /// T is a type for an invariant, such as a map
 pub trait State<T>: Copy + Eq + Hash {
@@ -113,170 +500,1424 @@ pub trait State<T>: Copy + Eq + Hash {
         found
     }
 
-    /// Minimum Steps from initial until predicate is true
-    fn steps<P>(initial: Self, predicate: P, invariant: &T) -> usize
+    /// Minimum Steps from initial until predicate is true, None if it never is
+    fn steps<P>(initial: Self, predicate: P, invariant: &T) -> Option<usize>
     where
         P: Fn(&Self) -> bool,
-        Self: std::fmt::Debug,
     {
-        if predicate(&initial) {
-            return 0;
+        Self::bounded_steps(initial, predicate, invariant, &Limits::default()).ok()
+    }
+
+    /// Minimum Steps from initial until predicate is true, or why the search stopped without
+    /// finding such a State within the limits
+    fn bounded_steps<P>(
+        initial: Self,
+        predicate: P,
+        invariant: &T,
+        limits: &Limits,
+    ) -> Result<usize, Stopped>
+    where
+        P: Fn(&Self) -> bool,
+    {
+        steps_by(initial, predicate, limits, |layer| expand(layer, invariant))
+            .map(|(steps, _)| steps)
+    }
+
+    /// Best (fewest steps) number of State transitions from initial to goal
+    fn best(initial: Self, goal: Self, invariant: &T) -> Option<usize> {
+        Self::steps(initial, |&s| s == goal, invariant)
+    }
+
+    /// Minimum Steps for each leg of a route from initial which satisfies each of the goals in
+    /// turn, each leg starts from the State which satisfied the previous goal
+    fn waypoints(initial: Self, goals: &[Goal<Self>], invariant: &T) -> Option<Legs<Self>> {
+        let mut legs = Legs {
+            steps: Vec::with_capacity(goals.len()),
+            end: initial,
+        };
+        for goal in goals {
+            let limits = Limits::default();
+            let expand = |layer| expand(layer, invariant);
+            let (steps, end) = steps_by(legs.end, goal, &limits, expand).ok()?;
+            legs.steps.push(steps);
+            legs.end = end;
         }
+        Some(legs)
+    }
 
-        let mut seen: HashSet<Self> = HashSet::new();
-        seen.insert(initial);
-        let mut current: Vec<Self> = vec![initial];
-        let mut steps = 0;
+    /// As best, but searching both forward from initial and backward from goal until the searches
+    /// meet, which explores far fewer States, reverse gives the States from which a State can be
+    /// reached, or if it is None the transitions must be reversible so that next can be used
+    fn bidirectional(
+        initial: Self,
+        goal: Self,
+        invariant: &T,
+        reverse: Option<Transition<Self, T>>,
+    ) -> Option<usize> {
+        let forward = |state: &Self, invariant: &T| state.next(invariant);
+        let reverse = reverse.unwrap_or(&forward);
 
-        loop {
+        if initial == goal {
+            return Some(0);
+        }
+        // Steps from initial (or to goal) for every State seen by each search
+        let mut ahead: HashMap<Self, usize> = HashMap::from([(initial, 0)]);
+        let mut behind: HashMap<Self, usize> = HashMap::from([(goal, 0)]);
+        let mut front: Vec<Self> = vec![initial];
+        let mut back: Vec<Self> = vec![goal];
+
+        while !front.is_empty() && !back.is_empty() {
+            // Always grow the smaller frontier by a whole layer, the first layer to meet the other
+            // search contains a shortest route, but not necessarily in its first meeting State
+            let (current, seen, other, transition) = if front.len() <= back.len() {
+                (
+                    &mut front,
+                    &mut ahead,
+                    &behind,
+                    &forward as Transition<Self, T>,
+                )
+            } else {
+                (&mut back, &mut behind, &ahead, reverse)
+            };
             let mut next: Vec<Self> = Vec::new();
-            for state in current {
-                let more = state.next(invariant);
-                for state in more {
-                    if predicate(&state) {
-                        return steps + 1;
+            let mut best: Option<usize> = None;
+            for state in current.iter() {
+                let steps = seen[state] + 1;
+                for new in transition(state, invariant) {
+                    if let Some(more) = other.get(&new) {
+                        best = Some(best.map_or(steps + more, |b| b.min(steps + more)));
                     }
-                    if !seen.contains(&state) {
-                        seen.insert(state);
-                        next.push(state);
+                    if let hash_map::Entry::Vacant(e) = seen.entry(new) {
+                        e.insert(steps);
+                        next.push(new);
                     }
                 }
             }
-            if next.is_empty() {
-                panic!("No route to achieve predicate on {initial:?}");
+            if best.is_some() {
+                return best;
             }
-            steps += 1;
-            current = next;
+            *current = next;
         }
+        None
     }
 
-    /// Best (fewest steps) number of State transitions from initial to goal
-    fn best(initial: Self, goal: Self, invariant: &T) -> usize
+    /// Minimum Steps from initial until predicate is true using iterative deepening depth first
+    /// search, which needs only memory for the current route rather than every State seen, but
+    /// repeats work at each depth, the states limit is on the total States visited
+    fn deepening<P>(
+        initial: Self,
+        predicate: P,
+        invariant: &T,
+        limits: &Limits,
+    ) -> Result<usize, Stopped>
     where
-        Self: std::fmt::Debug,
+        P: Fn(&Self) -> bool,
     {
-        Self::steps(initial, |&s| s == goal, invariant)
+        if predicate(&initial) {
+            return Ok(0);
+        }
+
+        let budget = Budget::new(limits);
+        let mut visited = 0;
+        let mut limit = 1;
+        loop {
+            budget.depth(limit - 1)?;
+            // Whether any route was cut short by the limit, if not there's no point going deeper
+            let mut cut = false;
+            let mut route: Vec<Self> = vec![initial];
+            let mut stack: Vec<Vec<Self>> = vec![initial.next(invariant)];
+
+            while let Some(children) = stack.last_mut() {
+                let Some(state) = children.pop() else {
+                    stack.pop();
+                    route.pop();
+                    continue;
+                };
+                budget.check(visited)?;
+                visited += 1;
+                if route.contains(&state) {
+                    continue;
+                }
+                if predicate(&state) {
+                    return Ok(route.len());
+                }
+                if route.len() == limit {
+                    cut = true;
+                    continue;
+                }
+                route.push(state);
+                stack.push(state.next(invariant));
+            }
+            if !cut {
+                return Err(Stopped::Unreachable);
+            }
+            limit += 1;
+        }
     }
 
     /// Possible states after up to steps taken
     fn count(initial: Self, steps: usize, invariant: &T) -> usize {
-        let mut seen: HashSet<Self> = HashSet::new();
-        seen.insert(initial);
-        let mut current: Vec<Self> = vec![initial];
+        count_by(initial, steps, |layer| expand(layer, invariant))
+    }
 
-        for _ in 0..steps {
-            let mut next: Vec<Self> = Vec::new();
-            for state in current {
-                let more = state.next(invariant);
-                for state in more {
-                    if !seen.contains(&state) {
-                        seen.insert(state);
-                        next.push(state);
-                    }
+    /// Route from initial to goal with the fewest State transitions, if there is one
+    fn route(initial: Self, goal: Self, invariant: &T) -> Option<Route<Self>> {
+        Self::bounded_route(initial, goal, invariant, &Limits::default()).ok()
+    }
+
+    /// Route from initial to goal with the fewest State transitions, or why the search stopped
+    /// without finding one within the limits
+    fn bounded_route(
+        initial: Self,
+        goal: Self,
+        invariant: &T,
+        limits: &Limits,
+    ) -> Result<Route<Self>, Stopped> {
+        route_by(initial, goal, limits, |layer| expand(layer, invariant))
+    }
+
+    /// The States from initial to goal inclusive, with the fewest State transitions
+    fn path(initial: Self, goal: Self, invariant: &T) -> Option<Vec<Self>> {
+        let route = Self::route(initial, goal, invariant)?;
+        let mut path: Vec<Self> = route.backtrack().collect();
+        path.reverse();
+        Some(path)
+    }
+
+    /// As bounded_steps, but expanding each layer of the search across threads
+    #[cfg(feature = "parallel")]
+    fn par_bounded_steps<P>(
+        initial: Self,
+        predicate: P,
+        invariant: &T,
+        limits: &Limits,
+    ) -> Result<usize, Stopped>
+    where
+        P: Fn(&Self) -> bool,
+        Self: Send + Sync,
+        T: Sync,
+    {
+        let threads = parallelism();
+        steps_by(initial, predicate, limits, |layer| {
+            par_expand(layer, invariant, threads)
+        })
+        .map(|(steps, _)| steps)
+    }
+
+    /// As count, but expanding each layer of the search across threads
+    #[cfg(feature = "parallel")]
+    fn par_count(initial: Self, steps: usize, invariant: &T) -> usize
+    where
+        Self: Send + Sync,
+        T: Sync,
+    {
+        let threads = parallelism();
+        count_by(initial, steps, |layer| {
+            par_expand(layer, invariant, threads)
+        })
+    }
+
+    /// As bounded_route, but expanding each layer of the search across threads
+    #[cfg(feature = "parallel")]
+    fn par_bounded_route(
+        initial: Self,
+        goal: Self,
+        invariant: &T,
+        limits: &Limits,
+    ) -> Result<Route<Self>, Stopped>
+    where
+        Self: Send + Sync,
+        T: Sync,
+    {
+        let threads = parallelism();
+        route_by(initial, goal, limits, |layer| {
+            par_expand(layer, invariant, threads)
+        })
+    }
+
+    /// Report (if debug is true) the States from the goal back to the initial
+    fn report(initial: Self, goal: Self, invariant: &T, debug: bool) -> Option<Self> {
+        let route = Self::route(initial, goal, invariant)?;
+        if debug {
+            for state in route.backtrack().take_while(|&s| s != initial) {
+                println!("D: {}", state.describe(invariant));
+            }
+            println!("D: {} distinct states seen", route.parents.len());
+        }
+        Some(route.goal())
+    }
+}
+
+/// Each State in a layer of a breadth-first search with the States reachable from it, in order,
+/// lazily so that a search which stops part way through a layer doesn't expand the rest of it
+fn expand<'a, S: State<T> + 'a, T>(
+    layer: Vec<S>,
+    invariant: &'a T,
+) -> impl Iterator<Item = (S, Vec<S>)> + 'a {
+    layer
+        .into_iter()
+        .map(move |state| (state, state.next(invariant)))
+}
+
+#[cfg(feature = "parallel")]
+fn parallelism() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// As expand, but each of the threads expands a contiguous chunk of the layer, so that the results
+/// are in the same order as for expand and a search finds exactly the same answer, however the
+/// whole layer is expanded before the search looks at any of it
+#[cfg(feature = "parallel")]
+fn par_expand<S, T>(layer: Vec<S>, invariant: &T, threads: usize) -> Vec<(S, Vec<S>)>
+where
+    S: State<T> + Send + Sync,
+    T: Sync,
+{
+    // Starting threads costs more than expanding a few States
+    const SMALL: usize = 64;
+
+    if threads < 2 || layer.len() < SMALL {
+        return expand(layer, invariant).collect();
+    }
+    let chunk = layer.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = layer
+            .chunks(chunk)
+            .map(|chunk| scope.spawn(|| expand(chunk.to_vec(), invariant).collect::<Vec<_>>()))
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .collect()
+    })
+}
+
+/// Breadth-first search for Steps until predicate is true, and the State for which it was true,
+/// expand finds the next States for a layer
+fn steps_by<S, P, E, I>(
+    initial: S,
+    predicate: P,
+    limits: &Limits,
+    expand: E,
+) -> Result<(usize, S), Stopped>
+where
+    S: Copy + Eq + Hash,
+    P: Fn(&S) -> bool,
+    E: Fn(Vec<S>) -> I,
+    I: IntoIterator<Item = (S, Vec<S>)>,
+{
+    if predicate(&initial) {
+        return Ok((0, initial));
+    }
+
+    let budget = Budget::new(limits);
+    let mut seen: HashSet<S> = HashSet::new();
+    seen.insert(initial);
+    let mut current: Vec<S> = vec![initial];
+    let mut steps = 0;
+
+    while !current.is_empty() {
+        budget.depth(steps)?;
+        let mut next: Vec<S> = Vec::new();
+        let mut layer = expand(current).into_iter();
+        loop {
+            // Check before expanding each State
+            budget.check(seen.len())?;
+            let Some((_, more)) = layer.next() else {
+                break;
+            };
+            for state in more {
+                if predicate(&state) {
+                    return Ok((steps + 1, state));
+                }
+                if !seen.contains(&state) {
+                    seen.insert(state);
+                    next.push(state);
                 }
             }
-            current = next;
         }
-        seen.len()
+        steps += 1;
+        current = next;
     }
+    Err(Stopped::Unreachable)
+}
 
-    /// Report (if debug is true) the States from the goal back to the initial
-    fn report(initial: Self, goal: Self, invariant: &T, debug: bool) -> Self {
-        if initial == goal {
-            return initial;
+/// Breadth-first count of States seen after up to steps, expand finds the next States for a layer
+fn count_by<S, E, I>(initial: S, steps: usize, expand: E) -> usize
+where
+    S: Copy + Eq + Hash,
+    E: Fn(Vec<S>) -> I,
+    I: IntoIterator<Item = (S, Vec<S>)>,
+{
+    let mut seen: HashSet<S> = HashSet::new();
+    seen.insert(initial);
+    let mut current: Vec<S> = vec![initial];
+
+    for _ in 0..steps {
+        let mut next: Vec<S> = Vec::new();
+        for (_, more) in expand(current) {
+            for state in more {
+                if !seen.contains(&state) {
+                    seen.insert(state);
+                    next.push(state);
+                }
+            }
         }
+        current = next;
+    }
+    seen.len()
+}
 
-        let mut seen: HashMap<Self, Self> = HashMap::new();
-        seen.insert(initial, initial);
-        let mut current: Vec<Self> = Vec::with_capacity(1);
-        current.push(initial);
+/// Breadth-first search for a Route to goal, expand finds the next States for a layer
+fn route_by<S, E, I>(initial: S, goal: S, limits: &Limits, expand: E) -> Result<Route<S>, Stopped>
+where
+    S: Copy + Eq + Hash,
+    E: Fn(Vec<S>) -> I,
+    I: IntoIterator<Item = (S, Vec<S>)>,
+{
+    let budget = Budget::new(limits);
+    let mut steps = 0;
+    let mut parents: HashMap<S, S> = HashMap::new();
+    parents.insert(initial, initial);
+    if initial == goal {
+        return Ok(Route { goal, parents });
+    }
+    let mut current: Vec<S> = vec![initial];
 
+    while !current.is_empty() {
+        budget.depth(steps)?;
+        let mut next: Vec<S> = Vec::new();
+        let mut layer = expand(current).into_iter();
         loop {
-            let mut next: Vec<Self> = Vec::new();
-            for state in current {
-                let more = state.next(invariant);
-                for new in more {
-                    if let hash_map::Entry::Vacant(e) = seen.entry(new) {
-                        e.insert(state);
-                        next.push(new);
-                    }
-                    if new == goal {
-                        if debug {
-                            let mut prev = &new;
-                            while prev != &initial {
-                                println!("D: {}", prev.describe(invariant));
-                                prev = seen.get(prev).unwrap();
-                            }
+            // Check before expanding each State
+            budget.check(parents.len())?;
+            let Some((state, more)) = layer.next() else {
+                break;
+            };
+            for new in more {
+                if let hash_map::Entry::Vacant(e) = parents.entry(new) {
+                    e.insert(state);
+                    next.push(new);
+                }
+                if new == goal {
+                    return Ok(Route { goal, parents });
+                }
+            }
+        }
+        steps += 1;
+        current = next;
+    }
+    Err(Stopped::Unreachable)
+}
+
+/// Limits on a search, by default there are none
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Most State transitions from the initial State
+    pub depth: Option<usize>,
+    /// Most distinct States seen
+    pub states: Option<usize>,
+    /// Longest time spent searching
+    pub time: Option<Duration>,
+}
+
+/// Why a search stopped without finding what it was looking for
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Stopped {
+    /// Every reachable State was searched
+    Unreachable,
+    /// The depth limit was reached
+    Depth,
+    /// The limit on distinct States was reached
+    States,
+    /// The time limit was reached
+    Time,
+}
+
+impl fmt::Display for Stopped {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stopped::Unreachable => write!(f, "no reachable state satisfies the search"),
+            Stopped::Depth => write!(f, "search reached its depth limit"),
+            Stopped::States => write!(f, "search reached its limit on distinct states"),
+            Stopped::Time => write!(f, "search reached its time limit"),
+        }
+    }
+}
+
+impl std::error::Error for Stopped {}
+
+/// Limits together with when the search started
+struct Budget<'l> {
+    limits: &'l Limits,
+    start: Instant,
+}
+
+impl<'l> Budget<'l> {
+    fn new(limits: &'l Limits) -> Self {
+        Budget {
+            limits,
+            start: Instant::now(),
+        }
+    }
+
+    fn depth(&self, steps: usize) -> Result<(), Stopped> {
+        match self.limits.depth {
+            Some(depth) if steps >= depth => Err(Stopped::Depth),
+            _ => Ok(()),
+        }
+    }
+
+    fn check(&self, seen: usize) -> Result<(), Stopped> {
+        if matches!(self.limits.states, Some(states) if seen >= states) {
+            return Err(Stopped::States);
+        }
+        if matches!(self.limits.time, Some(time) if self.start.elapsed() >= time) {
+            return Err(Stopped::Time);
+        }
+        Ok(())
+    }
+}
+
+/// The predecessor of each State seen while searching for a goal, so that the route to the goal
+/// can be followed back to the initial State, which is its own predecessor
+pub struct Route<S> {
+    goal: S,
+    parents: HashMap<S, S>,
+}
+
+impl<S: Copy + Eq + Hash> Route<S> {
+    pub fn goal(&self) -> S {
+        self.goal
+    }
+
+    /// Number of State transitions from initial to goal
+    pub fn steps(&self) -> usize {
+        self.backtrack().count() - 1
+    }
+
+    /// Iterate over the States from the goal back to the initial State
+    pub fn backtrack(&self) -> Backtrack<'_, S> {
+        Backtrack {
+            parents: &self.parents,
+            next: Some(self.goal),
+        }
+    }
+}
+
+/// Iterator over States from a goal back to the initial State
+pub struct Backtrack<'r, S> {
+    parents: &'r HashMap<S, S>,
+    next: Option<S>,
+}
+
+impl<S: Copy + Eq + Hash> Iterator for Backtrack<'_, S> {
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        let state = self.next?;
+        let parent = self.parents[&state];
+        self.next = (parent != state).then_some(parent);
+        Some(state)
+    }
+}
+
+/// Like State but each transition has a cost, T is a type for an invariant, such as a map
+pub trait Weighted<T>: Copy + Eq + Hash {
+    /// Each state reachable from this one, with the cost of getting there
+    fn next(&self, invariant: &T) -> Vec<(Self, usize)>;
+
+    /// Cheapest cost from initial until predicate is true, and the States visited on the way
+    /// including both initial and the State which satisfied predicate, using Dijkstra's algorithm
+    fn dijkstra<P>(initial: Self, predicate: P, invariant: &T) -> Option<(usize, Vec<Self>)>
+    where
+        P: Fn(&Self) -> bool,
+    {
+        Self::astar(initial, predicate, |_| 0, invariant)
+    }
+
+    /// As dijkstra but guided by A* heuristic, an estimate of the remaining cost from a State
+    /// which must never be more than the true cost or the result may not be the cheapest
+    fn astar<P, H>(
+        initial: Self,
+        predicate: P,
+        heuristic: H,
+        invariant: &T,
+    ) -> Option<(usize, Vec<Self>)>
+    where
+        P: Fn(&Self) -> bool,
+        H: Fn(&Self) -> usize,
+    {
+        // States are identified by their index in states, so they needn't be Ord
+        let mut states: Vec<Self> = vec![initial];
+        let mut index: HashMap<Self, usize> = HashMap::new();
+        index.insert(initial, 0);
+        // Cheapest known cost, and the previous state on that route, for each state
+        let mut cheapest: Vec<(usize, usize)> = vec![(0, 0)];
+        let mut open = BinaryHeap::new();
+        open.push(Reverse((heuristic(&initial), 0, 0)));
+
+        while let Some(Reverse((_, cost, k))) = open.pop() {
+            if cost > cheapest[k].0 {
+                continue;
+            }
+            let state = states[k];
+            if predicate(&state) {
+                let mut path = vec![state];
+                let mut k = k;
+                while k != 0 {
+                    k = cheapest[k].1;
+                    path.push(states[k]);
+                }
+                path.reverse();
+                return Some((cost, path));
+            }
+            for (new, step) in state.next(invariant) {
+                let cost = cost + step;
+                let n = match index.entry(new) {
+                    hash_map::Entry::Occupied(e) => {
+                        let n = *e.get();
+                        if cost >= cheapest[n].0 {
+                            continue;
                         }
-                        return new;
+                        cheapest[n] = (cost, k);
+                        n
+                    }
+                    hash_map::Entry::Vacant(e) => {
+                        let n = states.len();
+                        e.insert(n);
+                        states.push(new);
+                        cheapest.push((cost, k));
+                        n
                     }
+                };
+                open.push(Reverse((cost + heuristic(&new), cost, n)));
+            }
+        }
+        None
+    }
+}
+
+/// Like State but which transitions are possible depends on the time, for example because the
+/// obstacles move, T is a type for an invariant, such as a map
+pub trait Timed<T>: Copy + Eq + Hash {
+    /// Each State which can be reached from this one, arriving at tick
+    fn next(&self, tick: usize, invariant: &T) -> Vec<Self>;
+
+    /// If the environment repeats every period ticks, the same State at ticks which differ by a
+    /// multiple of the period is equivalent, otherwise States are only the same at the same tick
+    fn period(_invariant: &T) -> Option<usize> {
+        None
+    }
+
+    /// Minimum ticks after starting from initial at tick start until predicate is true, None if
+    /// it never is, if there's no period and waiting is possible this may never finish
+    fn steps<P>(initial: Self, start: usize, predicate: P, invariant: &T) -> Option<usize>
+    where
+        P: Fn(&Self) -> bool,
+    {
+        Self::bounded_steps(initial, start, predicate, invariant, &Limits::default()).ok()
+    }
+
+    /// Minimum ticks after starting from initial at tick start until predicate is true, or why
+    /// the search stopped without finding such a State within the limits
+    fn bounded_steps<P>(
+        initial: Self,
+        start: usize,
+        predicate: P,
+        invariant: &T,
+        limits: &Limits,
+    ) -> Result<usize, Stopped>
+    where
+        P: Fn(&Self) -> bool,
+    {
+        timed_by(initial, start, predicate, invariant, limits).map(|(steps, _)| steps)
+    }
+
+    /// Best (fewest ticks) to get from initial at tick start to goal
+    fn best(initial: Self, start: usize, goal: Self, invariant: &T) -> Option<usize> {
+        Self::steps(initial, start, |&s| s == goal, invariant)
+    }
+
+    /// Minimum ticks for each leg of a route from initial at tick start which satisfies each of
+    /// the goals in turn, each leg starts from the State which satisfied the previous goal
+    fn waypoints(
+        initial: Self,
+        start: usize,
+        goals: &[Goal<Self>],
+        invariant: &T,
+    ) -> Option<Legs<Self>> {
+        let mut legs = Legs {
+            steps: Vec::with_capacity(goals.len()),
+            end: initial,
+        };
+        for goal in goals {
+            let tick = start + legs.total();
+            let limits = Limits::default();
+            let (steps, end) = timed_by(legs.end, tick, goal, invariant, &limits).ok()?;
+            legs.steps.push(steps);
+            legs.end = end;
+        }
+        Some(legs)
+    }
+}
+
+/// A State in a puzzle which asks for the best score achievable in a fixed number of steps, T is a
+/// type for an invariant, such as a map
+pub trait Optimise<T>: Copy + Ord {
+    /// Each State reachable from this one, remaining is the steps left after this step
+    fn next(&self, remaining: usize, invariant: &T) -> Vec<Self>;
+
+    /// Score this State is sure to achieve, with remaining steps left
+    fn score(&self, remaining: usize, invariant: &T) -> usize;
+
+    /// Best score which could possibly be achieved from this State with remaining steps left, if
+    /// this is ever less than the true best score the search may miss the best score
+    fn bound(&self, remaining: usize, invariant: &T) -> usize;
+
+    /// Best score achievable in steps from initial, and the State which achieves it, expanding
+    /// each step but discarding States whose bound cannot beat the best score so far, and if beam
+    /// is Some keeping only that many of the highest scoring States at each step, which is faster
+    /// but may miss the best score
+    fn optimise(initial: Self, steps: usize, invariant: &T, beam: Option<usize>) -> (usize, Self) {
+        let mut best = (initial.score(steps, invariant), initial);
+        let mut current: Vec<Self> = vec![initial];
+
+        for remaining in (0..steps).rev() {
+            let mut next: Vec<Self> = Vec::new();
+            for state in current {
+                next.append(&mut state.next(remaining, invariant));
+            }
+            // De-duplication requires a sorted slice
+            next.sort_unstable();
+            next.dedup();
+            for state in next.iter() {
+                let score = state.score(remaining, invariant);
+                if score > best.0 {
+                    best = (score, *state);
                 }
             }
-            if debug {
-                println!("D: {} distinct states seen", seen.len());
-                println!("D: {} new states this iteration", next.len());
+
+            // Eliminate possibilities that can't beat the leader
+            next.retain(|state| state.bound(remaining, invariant) >= best.0);
+            if let Some(width) = beam {
+                if next.len() > width {
+                    next.select_nth_unstable_by_key(width, |state| {
+                        let score = state.score(remaining, invariant);
+                        Reverse((score, state.bound(remaining, invariant)))
+                    });
+                    next.truncate(width);
+                }
             }
             current = next;
         }
+        best
     }
 }
 
-/// Number of permutations of n things is n!
-pub const fn permutations(n: usize) -> usize {
-    match n {
-        1 => 1,
-        2 => 2,
-        3 => 6,
-        4 => 24,
-        5 => 120,
-        6 => 720,
-        7 => 5040,
-        8 => 40320,
-        9 => 362880,
-        10 => 3628800,
-        _ => {
-            panic!("Unknown permutations");
+/// Breadth-first search for ticks after start until predicate is true, and the State for which it
+/// was true
+fn timed_by<S, T, P>(
+    initial: S,
+    start: usize,
+    predicate: P,
+    invariant: &T,
+    limits: &Limits,
+) -> Result<(usize, S), Stopped>
+where
+    S: Timed<T>,
+    P: Fn(&S) -> bool,
+{
+    if predicate(&initial) {
+        return Ok((0, initial));
+    }
+
+    let budget = Budget::new(limits);
+    let period = S::period(invariant);
+    // Without a period the States seen in earlier ticks can be forgotten
+    let phase = |tick: usize| period.map_or(tick, |p| tick % p);
+    let mut seen: HashSet<(S, usize)> = HashSet::new();
+    seen.insert((initial, phase(start)));
+    let mut distinct = 1;
+    let mut current: Vec<S> = vec![initial];
+    let mut steps = 0;
+
+    while !current.is_empty() {
+        budget.depth(steps)?;
+        let tick = start + steps + 1;
+        if period.is_none() {
+            seen.clear();
         }
+        let mut next: Vec<S> = Vec::new();
+        for state in current {
+            budget.check(distinct)?;
+            for new in state.next(tick, invariant) {
+                if predicate(&new) {
+                    return Ok((steps + 1, new));
+                }
+                if seen.insert((new, phase(tick))) {
+                    distinct += 1;
+                    next.push(new);
+                }
+            }
+        }
+        steps += 1;
+        current = next;
+    }
+    Err(Stopped::Unreachable)
+}
+
+/// Number of permutations of n things is n!, panics if that doesn't fit in a usize
+pub const fn permutations(n: usize) -> usize {
+    match factorial(n) {
+        Some(n) => n,
+        None => panic!("Too many permutations"),
     }
 }
 
-/// Heap's Algorithm for permuting slices up to length 10
+/// n! or None if that doesn't fit in a usize, see big::factorial for larger values
+pub const fn factorial(n: usize) -> Option<usize> {
+    let mut product: usize = 1;
+    let mut k = 2;
+    while k <= n {
+        product = match product.checked_mul(k) {
+            Some(product) => product,
+            None => return None,
+        };
+        k += 1;
+    }
+    Some(product)
+}
+
+const fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Number of ways to choose k things from n, or None if that doesn't fit in a usize, see
+/// big::binomial for larger values
+pub const fn binomial(n: usize, k: usize) -> Option<usize> {
+    if k > n {
+        return Some(0);
+    }
+    let k = if k > n - k { n - k } else { k };
+    let mut result: usize = 1;
+    let mut i = 1;
+    while i <= k {
+        // result * (n - k + i) is divisible by i, dividing first by their common factor keeps
+        // the intermediate values no larger than the result
+        let g = gcd(result, i);
+        result = match (result / g).checked_mul((n - k + i) / (i / g)) {
+            Some(result) => result,
+            None => return None,
+        };
+        i += 1;
+    }
+    Some(result)
+}
+
+/// Heap's Algorithm for permuting slices, step n turns the (n-1)th permutation into the nth
 /// handle zero specially to do nothing
 pub fn heap<T>(a: &mut [T], n: usize) {
-    assert!(a.len() < 11);
     if n == 0 {
-        //return;
-    } else if n % 2 == 1 {
-        a.swap(0, 1);
-    } else if n % 6 > 0 {
-        a.swap(0, 2);
-    } else if n % 24 > 0 {
-        let p = (n / 8) % 3;
-        a.swap(p, 3);
-    } else if n % 120 > 0 {
-        a.swap(0, 4);
-    } else if n % 720 > 0 {
-        let p = (n / 144) % 5;
-        a.swap(p, 5);
-    } else if n % 5040 > 0 {
-        a.swap(0, 6);
-    } else if n % 40320 > 0 {
-        let p = (n / 5760) % 7;
-        a.swap(p, 7);
-    } else if n % 362880 > 0 {
-        a.swap(0, 8);
+        return;
+    }
+    // Write n in the factorial number system, if the lowest non-zero digit d is at position k
+    // then position k is swapped with position 0 if k is even, or else position d - 1
+    let mut m = n;
+    let mut k = 1;
+    while m.is_multiple_of(k + 1) {
+        m /= k + 1;
+        k += 1;
+    }
+    let d = m % (k + 1);
+    if k % 2 == 1 {
+        a.swap(d - 1, k);
     } else {
-        let p = (n / 403200) % 9;
-        a.swap(p, 9);
+        a.swap(0, k);
     }
 }
 
+pub mod answers;
+#[cfg(feature = "big")]
+pub mod big;
+pub mod grid;
+pub mod image;
 pub mod map;
+pub mod permute;
 
 #[cfg(test)]
-
 mod tests {
     use crate::heap;
+    use crate::{Answer, Contents, Day, Input, ParseError, Part, Registry, State, Weighted};
+    use crate::{Limits, Optimise, Stopped, Timed};
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    fn contents(text: &str) -> Contents {
+        Contents {
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn try_numbers() {
+        let ctxt = contents("12\n-4\nx7\n");
+        let v: Vec<_> = ctxt.try_numbers().collect();
+        assert_eq!(v[0], Ok(12));
+        assert_eq!(v[1], Ok(-4));
+        let e = v[2].clone().unwrap_err();
+        assert_eq!((e.line, e.column), (3, 1));
+        assert_eq!(e.text, "x7");
+        assert_eq!(ctxt.numbers().collect::<Vec<_>>(), [12, -4, 0]);
+    }
+
+    #[test]
+    fn try_binary() {
+        let ctxt = contents(
+            "101
+0
+1021
+",
+        );
+        let v: Vec<_> = ctxt.try_binary().collect();
+        assert_eq!(v[0], Ok(5));
+        assert_eq!(v[1], Ok(0));
+        let e = v[2].clone().unwrap_err();
+        assert_eq!((e.line, e.column), (3, 1));
+        assert_eq!(e.text, "1021");
+        assert_eq!(e.reason, "invalid digit found in string");
+        assert_eq!(ctxt.binary().collect::<Vec<_>>(), [5, 0]);
+    }
+
+    #[test]
+    fn try_list_numbers() {
+        let ctxt = contents("3,4,,5\n");
+        let e: ParseError = ctxt
+            .try_list_numbers()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!((e.line, e.column), (1, 5));
+        assert_eq!(e.text, "");
+        assert_eq!(ctxt.try_number().unwrap_err().column, 1);
+    }
+
+    #[test]
+    fn try_digits() {
+        let ctxt = contents("\n  45z7\n");
+        let e = ctxt
+            .try_digits()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!((e.line, e.column), (2, 5));
+        assert_eq!(e.text, "z");
+        assert_eq!(
+            e.to_string(),
+            "line 2, column 5: not a decimal digit in \"z\""
+        );
+    }
+
+    fn lines(ctxt: &Contents) -> Answer {
+        ctxt.lines().count().into()
+    }
+
+    fn shout(ctxt: &Contents) -> Answer {
+        ctxt.value().to_uppercase().into()
+    }
+
+    #[test]
+    fn registry() {
+        let mut registry = Registry::new();
+        registry.add(Day::new(7, shout, lines));
+        registry.add(Day::new(2, lines, shout));
+        assert!(registry.get(1).is_none());
+        let parts: Vec<(u8, Part)> = registry.parts().collect();
+        assert_eq!(
+            parts,
+            [(2, Part::A), (2, Part::B), (7, Part::A), (7, Part::B)]
+        );
+
+        let ctxt = contents("one\ntwo\n");
+        let seven = registry.get(7).unwrap();
+        assert_eq!(
+            seven.solve(Part::A, &ctxt),
+            Answer::Text("ONE\nTWO".to_string())
+        );
+        assert_eq!(seven.solve(Part::B, &ctxt), Answer::Number(2));
+        assert_eq!(seven.b(&ctxt).to_string(), "2");
+    }
+
+    #[test]
+    fn input_paths() {
+        let real = Input::Dir {
+            dir: "inputs".into(),
+            set: Input::REAL.to_string(),
+        };
+        assert_eq!(real.path(4), Some("inputs/04".into()));
+        let example = Input::Dir {
+            dir: "inputs".into(),
+            set: "example".to_string(),
+        };
+        assert_eq!(example.path(12), Some("inputs/12.example".into()));
+        assert_eq!(Input::file("mine").path(9), Some("mine".into()));
+        assert_eq!(Input::file("-"), Input::Stdin);
+        assert_eq!(Input::Stdin.path(9), None);
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    struct Number(u32);
+
+    impl State<u32> for Number {
+        fn describe(&self, _limit: &u32) -> String {
+            self.0.to_string()
+        }
+
+        fn next(&self, limit: &u32) -> Vec<Self> {
+            let v = [self.0 + 1, self.0 * 2];
+            v.into_iter().filter(|n| n <= limit).map(Number).collect()
+        }
+    }
+
+    #[test]
+    fn path() {
+        let path = Number::path(Number(1), Number(10), &100).unwrap();
+        assert_eq!(path, [1, 2, 4, 5, 10].map(Number));
+        let route = Number::route(Number(1), Number(10), &100).unwrap();
+        assert_eq!(route.steps(), 4);
+        let back: Vec<Number> = route.backtrack().collect();
+        assert_eq!(back, [10, 5, 4, 2, 1].map(Number));
+        assert_eq!(
+            Number::path(Number(3), Number(3), &100),
+            Some(vec![Number(3)])
+        );
+        assert!(Number::route(Number(1), Number(101), &100).is_none());
+        assert_eq!(
+            Number::report(Number(1), Number(10), &100, false),
+            Some(Number(10))
+        );
+    }
+
+    #[test]
+    fn waypoints() {
+        let even = |n: &Number| n.0 > 10 && n.0.is_multiple_of(2);
+        let odd = |n: &Number| n.0 > 10 && !n.0.is_multiple_of(2);
+        let legs = Number::waypoints(Number(1), &[&even, &odd, &even], &100).unwrap();
+        // 1, 2, 3, 6, 12 then 13 then 14
+        assert_eq!(legs.steps, [4, 1, 1]);
+        assert_eq!((legs.total(), legs.end), (6, Number(14)));
+        let none = Number::waypoints(Number(7), &[], &100).unwrap();
+        assert_eq!((none.total(), none.end), (0, Number(7)));
+        assert_eq!(
+            Number::waypoints(Number(1), &[&even, &|n| n.0 == 5], &100),
+            None
+        );
+    }
+
+    // Ten children each, counting how many States were expanded
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    struct Fan(u32);
+
+    impl State<Cell<usize>> for Fan {
+        fn describe(&self, _expanded: &Cell<usize>) -> String {
+            self.0.to_string()
+        }
+
+        fn next(&self, expanded: &Cell<usize>) -> Vec<Self> {
+            expanded.set(expanded.get() + 1);
+            (0..10).map(|k| Fan(self.0 * 10 + k + 1)).collect()
+        }
+    }
+
+    #[test]
+    fn streaming() {
+        // The goal is the first grandchild, so only the root and its first child are expanded
+        let expanded = Cell::new(0);
+        assert_eq!(Fan::best(Fan(0), Fan(11), &expanded), Some(2));
+        assert_eq!(expanded.get(), 2);
+
+        expanded.set(0);
+        let route = Fan::route(Fan(0), Fan(11), &expanded).unwrap();
+        assert_eq!(route.steps(), 2);
+        assert_eq!(expanded.get(), 2);
+
+        // The limit on States is checked before expanding each State, not after a whole layer
+        expanded.set(0);
+        let limits = Limits {
+            states: Some(15),
+            ..Limits::default()
+        };
+        let far = |f: &Fan| f.0 > 1_000_000;
+        assert_eq!(
+            Fan::bounded_steps(Fan(0), far, &expanded, &limits),
+            Err(Stopped::States)
+        );
+        assert_eq!(expanded.get(), 2);
+    }
+
+    #[test]
+    fn limits() {
+        let (one, ten) = (Number(1), Number(10));
+        assert_eq!(Number::best(one, ten, &100), Some(4));
+        assert_eq!(Number::best(one, Number(101), &100), None);
+        assert_eq!(Number::steps(one, |n| n.0 > 100, &100), None);
+        assert_eq!(Number::report(one, Number(101), &100, false), None);
+
+        let depth = |depth| Limits {
+            depth: Some(depth),
+            ..Limits::default()
+        };
+        let equal = |n: &Number| *n == ten;
+        assert_eq!(Number::bounded_steps(one, equal, &100, &depth(4)), Ok(4));
+        assert_eq!(
+            Number::bounded_steps(one, equal, &100, &depth(3)),
+            Err(Stopped::Depth)
+        );
+        assert!(Number::bounded_route(one, ten, &100, &depth(4)).is_ok());
+        assert_eq!(
+            Number::bounded_route(one, ten, &100, &depth(3)).err(),
+            Some(Stopped::Depth)
+        );
+
+        let states = Limits {
+            states: Some(5),
+            ..Limits::default()
+        };
+        let far = |n: &Number| n.0 == 99;
+        assert_eq!(
+            Number::bounded_steps(one, far, &100, &states),
+            Err(Stopped::States)
+        );
+        let time = Limits {
+            time: Some(Duration::ZERO),
+            ..Limits::default()
+        };
+        assert_eq!(
+            Number::bounded_steps(one, far, &100, &time),
+            Err(Stopped::Time)
+        );
+        assert_eq!(
+            Number::bounded_steps(one, far, &50, &Limits::default()),
+            Err(Stopped::Unreachable)
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel() {
+        let layer: Vec<Number> = (1..=200).map(Number).collect();
+        let expanded = crate::par_expand(layer.clone(), &300, 4);
+        assert_eq!(expanded, crate::expand(layer, &300).collect::<Vec<_>>());
+
+        let (one, ten) = (Number(1), Number(10));
+        let limits = Limits::default();
+        assert_eq!(
+            Number::par_bounded_steps(one, |&n| n == ten, &100, &limits),
+            Ok(4)
+        );
+        assert_eq!(
+            Number::par_count(one, 5, &1000),
+            Number::count(one, 5, &1000)
+        );
+        let route = Number::par_bounded_route(one, ten, &100, &limits).unwrap();
+        assert_eq!(route.steps(), 4);
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    struct Knight(i8, i8);
+
+    impl State<i8> for Knight {
+        fn describe(&self, _size: &i8) -> String {
+            format!("{self:?}")
+        }
+
+        fn next(&self, size: &i8) -> Vec<Self> {
+            let jumps = [
+                (1, 2),
+                (2, 1),
+                (2, -1),
+                (1, -2),
+                (-1, -2),
+                (-2, -1),
+                (-2, 1),
+                (-1, 2),
+            ];
+            let on = |n: i8| (0..*size).contains(&n);
+            jumps
+                .iter()
+                .map(|(dx, dy)| Knight(self.0 + dx, self.1 + dy))
+                .filter(|k| on(k.0) && on(k.1))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn bidirectional() {
+        let corner = Knight(0, 0);
+        for (x, y) in [(0, 0), (1, 2), (7, 7), (6, 0), (1, 1), (3, 4)] {
+            let goal = Knight(x, y);
+            let best = Knight::best(corner, goal, &8);
+            assert!(best.is_some());
+            assert_eq!(Knight::bidirectional(corner, goal, &8, None), best);
+        }
+        assert_eq!(Knight::best(corner, Knight(7, 7), &8), Some(6));
+        assert_eq!(Knight::bidirectional(corner, Knight(1, 1), &3, None), None);
+
+        let reverse = |n: &Number, _limit: &u32| {
+            let mut v = vec![Number(n.0 - 1)];
+            if n.0.is_multiple_of(2) {
+                v.push(Number(n.0 / 2));
+            }
+            v.retain(|n| n.0 > 0);
+            v
+        };
+        for n in [1, 10, 31, 64, 99] {
+            let best = Number::best(Number(1), Number(n), &100);
+            let both = Number::bidirectional(Number(1), Number(n), &100, Some(&reverse));
+            assert_eq!(both, best);
+        }
+    }
+
+    #[test]
+    fn deepening() {
+        let corner = Knight(0, 0);
+        let limits = Limits::default();
+        for (x, y) in [(0, 0), (1, 2), (7, 7), (6, 0), (1, 1)] {
+            let goal = Knight(x, y);
+            let best = Knight::best(corner, goal, &8);
+            let deep = Knight::deepening(corner, |&k| k == goal, &8, &limits);
+            assert_eq!(deep.ok(), best);
+        }
+        assert_eq!(
+            Knight::deepening(corner, |&k| k == Knight(1, 1), &3, &limits),
+            Err(Stopped::Unreachable)
+        );
+        let shallow = Limits {
+            depth: Some(3),
+            ..Limits::default()
+        };
+        assert_eq!(
+            Knight::deepening(corner, |&k| k == Knight(7, 7), &8, &shallow),
+            Err(Stopped::Depth)
+        );
+        assert_eq!(
+            Number::deepening(Number(1), |&n| n == Number(10), &100, &limits),
+            Ok(4)
+        );
+    }
+
+    // A bridge with planks which are only present when (position + tick) is a multiple of three
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    struct Bridge(u8);
+
+    impl Timed<u8> for Bridge {
+        fn next(&self, tick: usize, length: &u8) -> Vec<Self> {
+            let present = |p: u8| p == 0 || p == *length || (p as usize + tick).is_multiple_of(3);
+            let mut v = Vec::new();
+            for p in [self.0, self.0 + 1, self.0.wrapping_sub(1)] {
+                if p <= *length && present(p) {
+                    v.push(Bridge(p));
+                }
+            }
+            v
+        }
+
+        fn period(_length: &u8) -> Option<usize> {
+            Some(3)
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    struct Drifting(Bridge);
+
+    impl Timed<u8> for Drifting {
+        fn next(&self, tick: usize, length: &u8) -> Vec<Self> {
+            self.0
+                .next(tick, length)
+                .into_iter()
+                .map(Drifting)
+                .collect()
+        }
+    }
+
+    #[test]
+    fn timed() {
+        // Plank 1 is present at ticks 2, 5, 8 and plank 2 at ticks 1, 4, 7 so there's never a
+        // plank 2 to step onto from plank 1, and the planks vanish under anybody waiting on them
+        assert_eq!(Bridge::best(Bridge(0), 0, Bridge(3), &3), None);
+        assert_eq!(Bridge::best(Bridge(0), 0, Bridge(1), &3), Some(2));
+        assert_eq!(Bridge::best(Bridge(0), 1, Bridge(1), &3), Some(1));
+        assert_eq!(Bridge::best(Bridge(0), 0, Bridge(0), &3), Some(0));
+
+        // Without a period the search only stops at the depth limit
+        let limits = Limits {
+            depth: Some(30),
+            ..Limits::default()
+        };
+        let across = |d: &Drifting| d.0 == Bridge(3);
+        assert_eq!(
+            Drifting::bounded_steps(Drifting(Bridge(0)), 0, across, &3, &limits),
+            Err(Stopped::Depth)
+        );
+        let legs = Bridge::waypoints(Bridge(0), 0, &[&|b| b.0 == 1, &|b| b.0 == 0], &3);
+        let legs = legs.unwrap();
+        // Back to plank 0 at tick 3, plank 1 has gone by then
+        assert_eq!(legs.steps, [2, 1]);
+        assert_eq!((legs.total(), legs.end), (3, Bridge(0)));
+        assert_eq!(Bridge::waypoints(Bridge(0), 0, &[&|b| b.0 == 3], &3), None);
+
+        let one = |d: &Drifting| d.0 == Bridge(1);
+        assert_eq!(
+            Drifting::bounded_steps(Drifting(Bridge(0)), 0, one, &3, &limits),
+            Ok(2)
+        );
+    }
+
+    const ITEMS: [(usize, usize); 8] = [
+        (5, 4),
+        (7, 5),
+        (2, 9),
+        (1, 2),
+        (8, 9),
+        (6, 3),
+        (4, 4),
+        (3, 6),
+    ];
+
+    // Decide for each item in turn whether to pack it, the score is the value of what's packed
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Knapsack {
+        item: usize,
+        weight: usize,
+        value: usize,
+    }
+
+    impl Optimise<usize> for Knapsack {
+        fn next(&self, _remaining: usize, capacity: &usize) -> Vec<Self> {
+            let Some(&(weight, value)) = ITEMS.get(self.item) else {
+                return Vec::new();
+            };
+            let skip = Knapsack {
+                item: self.item + 1,
+                ..*self
+            };
+            let pack = Knapsack {
+                item: self.item + 1,
+                weight: self.weight + weight,
+                value: self.value + value,
+            };
+            if pack.weight <= *capacity {
+                vec![skip, pack]
+            } else {
+                vec![skip]
+            }
+        }
+
+        fn score(&self, _remaining: usize, _capacity: &usize) -> usize {
+            self.value
+        }
+
+        fn bound(&self, _remaining: usize, _capacity: &usize) -> usize {
+            self.value + ITEMS[self.item..].iter().map(|&(_, v)| v).sum::<usize>()
+        }
+    }
+
+    #[test]
+    fn optimise() {
+        let empty = Knapsack {
+            item: 0,
+            weight: 0,
+            value: 0,
+        };
+        for capacity in [0, 3, 10, 15, 20, 40] {
+            let mut exhaustive = 0;
+            for mask in 0..(1 << ITEMS.len()) {
+                let packed = ITEMS
+                    .iter()
+                    .enumerate()
+                    .filter(|(k, _)| mask & (1 << k) != 0);
+                let (w, v) = packed.fold((0, 0), |(w, v), (_, i)| (w + i.0, v + i.1));
+                if w <= capacity {
+                    exhaustive = exhaustive.max(v);
+                }
+            }
+            let (best, state) = Knapsack::optimise(empty, ITEMS.len(), &capacity, None);
+            assert_eq!(best, exhaustive);
+            assert_eq!(state.value, best);
+            assert!(state.weight <= capacity);
+            let (beam, _) = Knapsack::optimise(empty, ITEMS.len(), &capacity, Some(4));
+            assert!(beam <= best);
+        }
+        let (wide, _) = Knapsack::optimise(empty, ITEMS.len(), &20, Some(1000));
+        assert_eq!(wide, Knapsack::optimise(empty, ITEMS.len(), &20, None).0);
+    }
+
+    const RISK: &str = "1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581
+";
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    struct Position(usize, usize);
+
+    impl Weighted<Vec<Vec<u8>>> for Position {
+        fn next(&self, risk: &Vec<Vec<u8>>) -> Vec<(Self, usize)> {
+            let Position(x, y) = *self;
+            let mut v = Vec::new();
+            let moves = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (x, y) in moves {
+                if let Some(&r) = risk.get(y).and_then(|row| row.get(x)) {
+                    v.push((Position(x, y), r as usize));
+                }
+            }
+            v
+        }
+    }
+
+    #[test]
+    fn weighted() {
+        let risk: Vec<Vec<u8>> = RISK
+            .lines()
+            .map(|line| line.bytes().map(|b| b - b'0').collect())
+            .collect();
+        let start = Position(0, 0);
+        let end = Position(9, 9);
+
+        let (cost, path) = Position::dijkstra(start, |&p| p == end, &risk).unwrap();
+        assert_eq!(cost, 40);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        let total: usize = path[1..].iter().map(|p| risk[p.1][p.0] as usize).sum();
+        assert_eq!(total, 40);
+
+        let distance = |p: &Position| (9 - p.0) + (9 - p.1);
+        let (cost, path) = Position::astar(start, |&p| p == end, distance, &risk).unwrap();
+        assert_eq!(cost, 40);
+        assert_eq!(path.len(), 19);
+
+        assert_eq!(
+            Position::dijkstra(start, |&p| p == start, &risk),
+            Some((0, vec![start]))
+        );
+        assert_eq!(Position::dijkstra(start, |&p| p.0 > 9, &risk), None);
+    }
 
     #[test]
     fn heap_two() {
@@ -24,41 +24,157 @@ mod day23;
 mod day24;
 mod day25;
 
-use jungle::days;
+mod run;
+
+use jungle::answers::Answers;
+use jungle::{days, readfile, Input};
+
+const USAGE: &str = "Usage: jungle [--dir DIR] [--set NAME] [--input FILE] [--json] [--check] [--answers FILE] DAY...
+  DAY           which day and part e.g. 1a means day 1, part A while 4b means day 4, part B
+                also 7* or 7 for both parts of day 7, 1-10 for days 1 to 10, or all
+  --dir DIR     directory holding inputs named for the day e.g. 04 (default $JUNGLE_INPUT or .)
+  --set NAME    named input set, e.g. example reads 04.example rather than 04 (default real)
+  --input FILE  read this file instead, or - to read standard input
+  --json        report answers and timings as JSON rather than a table
+  --check       compare answers with those expected for the input set, failing on any mismatch
+  --answers FILE  expected answers (default answers.toml in the input directory)";
+
+struct Options {
+    which: Vec<String>,
+    input: Input,
+    json: bool,
+    answers: Option<String>,
+}
+
+// Options from the command line, or None if only help was asked for
+fn options(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut which = Vec::new();
+    let mut dir = None;
+    let mut set = None;
+    let mut file = None;
+    let mut json = false;
+    let mut check = false;
+    let mut answers = None;
+
+    while let Some(arg) = args.next() {
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.into())),
+            _ => (arg, None),
+        };
+        let slot = match flag.as_str() {
+            "-d" | "--dir" => &mut dir,
+            "-s" | "--set" => &mut set,
+            "-i" | "--input" => &mut file,
+            "-a" | "--answers" => &mut answers,
+            "-j" | "--json" => {
+                json = true;
+                continue;
+            }
+            "-c" | "--check" => {
+                check = true;
+                continue;
+            }
+            "-h" | "--help" => return Ok(None),
+            _ if flag.starts_with("--") => return Err(format!("Unknown option {flag}")),
+            _ => {
+                which.push(flag.to_ascii_lowercase());
+                continue;
+            }
+        };
+        let value = value
+            .or_else(|| args.next())
+            .ok_or_else(|| format!("{flag} needs a value"))?;
+        *slot = Some(value);
+    }
+
+    if which.is_empty() {
+        return Err("Provide a parameter specifying which day e.g. 1a means day 1, part A while 4b means day 4, part B".to_string());
+    }
+    let input = match (file, dir, set) {
+        (Some(_), Some(_), _) | (Some(_), _, Some(_)) => {
+            return Err("--input cannot be combined with --dir or --set".to_string())
+        }
+        (Some(file), None, None) => Input::file(&file),
+        (None, dir, set) => Input::Dir {
+            dir: dir.map_or_else(Input::default_dir, Into::into),
+            set: set.unwrap_or_else(|| Input::REAL.to_string()),
+        },
+    };
+    let answers = match (check, answers) {
+        (false, None) => None,
+        (_, Some(answers)) => Some(answers),
+        (true, None) => {
+            let dir = match &input {
+                Input::Dir { dir, .. } => dir.clone(),
+                _ => ".".into(),
+            };
+            Some(dir.join("answers.toml").to_string_lossy().into_owned())
+        }
+    };
+    Ok(Some(Options {
+        which,
+        input,
+        json,
+        answers,
+    }))
+}
 
 fn main() {
     let mut args = std::env::args();
     args.next();
 
-    let day = args.next().expect("Provide a parameter specifying which day e.g. 1a means day 1, part A while 4b means day 4, part B").to_ascii_lowercase();
-    let day = format!("day{:0>3}", day);
+    let Some(Options {
+        which,
+        input,
+        json,
+        answers,
+    }) = options(args).unwrap_or_else(|e| {
+        eprintln!("{e}\n{USAGE}");
+        std::process::exit(2);
+    })
+    else {
+        println!("{USAGE}");
+        return;
+    };
 
-    days!(
-        day.as_str(),
-        day01,
-        day02,
-        day03,
-        day04,
-        day05,
-        day06,
-        day07,
-        day08,
-        day09,
-        day10,
-        day11,
-        day12,
-        day13,
-        day14,
-        day15,
-        day16,
-        day17,
-        day18,
-        day19,
-        day20,
-        day21,
-        day22,
-        day23,
-        day24,
-        day25,
+    // The days are modules of this binary, not the library, so they are registered here
+    let registry = days!(
+        day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
+        day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25,
     );
+
+    let parts = run::select(&registry, &which).unwrap_or_else(|e| {
+        eprintln!("{e}\n{USAGE}");
+        std::process::exit(2);
+    });
+    let expected = answers.map(|filename| {
+        let parsed = readfile(&filename)
+            .map_err(|e| e.to_string())
+            .and_then(|ctxt| Answers::parse(&ctxt).map_err(|e| format!("{filename}: {e}")));
+        parsed.unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(2);
+        })
+    });
+
+    let mut rows = run::run(&registry, &input, &parts);
+    if let Some(expected) = &expected {
+        run::check(&mut rows, expected, &input.set());
+    }
+
+    if json {
+        print!("{}", run::json(&rows));
+    } else if let ([row], None) = (rows.as_slice(), &expected) {
+        // Just one part, so just the answer
+        match &row.outcome {
+            Ok(answer) => println!("{answer}"),
+            Err(e) => eprintln!("{e}"),
+        }
+    } else {
+        print!("{}", run::table(&rows));
+    }
+
+    if !run::success(&rows) {
+        std::process::exit(1);
+    }
 }
@@ -1,13 +1,15 @@
+// One axis of a Map (or Grid), the allocated positions from offset for size, and the live
+// positions from start to end inclusive
 #[derive(Copy, Clone, Debug)]
-struct Plane {
-    size: isize,
-    offset: isize,
-    start: isize,
-    end: isize,
+pub(crate) struct Plane {
+    pub(crate) size: isize,
+    pub(crate) offset: isize,
+    pub(crate) start: isize,
+    pub(crate) end: isize,
 }
 
 impl Plane {
-    fn from_to(from: isize, to: isize) -> Self {
+    pub(crate) fn from_to(from: isize, to: isize) -> Self {
         if to < from {
             panic!("{from} to {to} is not reasonable for defining a Plane");
         }
@@ -21,9 +23,45 @@ impl Plane {
         }
     }
 
+    // Allocated like from_to, but with nothing live until the first position is included
+    pub(crate) fn vacant(from: isize, to: isize) -> Self {
+        Self {
+            start: isize::MAX,
+            end: isize::MIN,
+            ..Self::from_to(from, to)
+        }
+    }
+
+    // How many positions are live
+    pub(crate) fn span(&self) -> usize {
+        if self.end < self.start {
+            0
+        } else {
+            (self.end - self.start + 1) as usize
+        }
+    }
+
+    // A Plane which won't grow, every position from and to inclusive is live
+    fn fixed(from: isize, to: isize) -> Self {
+        if to < from {
+            panic!("{from} to {to} is not reasonable for defining a Plane");
+        }
+        Self {
+            size: to - from + 1,
+            offset: from,
+            start: from,
+            end: to,
+        }
+    }
+
+    // Wrap n into the Plane, as if the edges were joined
+    fn wrap(&self, n: isize) -> isize {
+        self.offset + (n - self.offset).rem_euclid(self.size)
+    }
+
     // Never shrink either end of the range, which might otherwise happen where Map::rect create
     // large uninitialised Maps
-    fn expand(&self) -> Self {
+    pub(crate) fn expand(&self) -> Self {
         const GROWTH: isize = 8;
 
         let offset = if self.offset < self.start - GROWTH {
@@ -38,18 +76,81 @@ impl Plane {
         };
         Self {
             size: width,
-            offset: offset,
+            offset,
             start: self.start,
             end: self.end,
         }
     }
 }
 
+// Width and height of each tile in a sparse Map
+const TILE: isize = 16;
+
+type Tile<T> = Box<[T; (TILE * TILE) as usize]>;
+
+/// Backing store for a Map
+#[derive(Clone)]
+enum Store<T> {
+    /// Every position in the rectangle described by the planes, row by row
+    Dense(Vec<T>),
+    /// Square tiles, only for areas where something was written
+    Sparse(HashMap<(isize, isize), Tile<T>>),
+}
+
+fn tile(x: isize, y: isize) -> ((isize, isize), usize) {
+    let k = y.rem_euclid(TILE) * TILE + x.rem_euclid(TILE);
+    ((x.div_euclid(TILE), y.div_euclid(TILE)), k as usize)
+}
+
+// Position of the kth value in tile t
+fn untile((tx, ty): (isize, isize), k: usize) -> (isize, isize) {
+    let (dx, dy) = (k as isize % TILE, k as isize / TILE);
+    (tx * TILE + dx, ty * TILE + dy)
+}
+
+// Position of the ith value in a dense Map
+fn coordinates(x: &Plane, y: &Plane, i: usize) -> (isize, isize) {
+    let row = y.offset + (i as isize / x.size);
+    let col = x.offset + (i as isize % x.size);
+    (col, row)
+}
+
+/// How a Map treats positions beyond its edges
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Topology {
+    /// The Map grows to include whatever is written, reads beyond the edges are None
+    #[default]
+    Growable,
+    /// Fixed size, reads beyond the edges are None and writes there are errors
+    Bounded,
+    /// Fixed size, but the opposite edges are joined so that every position wraps around
+    Torus,
+}
+
+/// A write beyond the edges of a Bounded Map
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OutOfBounds {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_fmt(format_args!(
+            "({}, {}) is beyond the edges of the Map",
+            self.x, self.y
+        ))
+    }
+}
+
+impl std::error::Error for OutOfBounds {}
+
 #[derive(Clone)]
 pub struct Map<T: Copy + Default> {
-    data: Vec<T>,
+    data: Store<T>,
     x: Plane,
     y: Plane,
+    topology: Topology,
 }
 
 impl<T: Copy + Default> Default for Map<T> {
@@ -61,7 +162,8 @@ impl<T: Copy + Default> Default for Map<T> {
 /// A type for 2D maps of unknown expanses, the backing store automatically grows as necessary
 /// Map<T> implements Debug and/or Display if they are implemented for T to conveniently show the
 /// map
-use std::ops::Range;
+use std::collections::HashMap;
+use std::ops::{Range, RangeInclusive};
 impl<T: Copy + Default> Map<T> {
     /// Map a Rectangle initially from (x1, y1) to (x2, y2) but it will grow automatically as
     /// necessary
@@ -79,13 +181,72 @@ impl<T: Copy + Default> Map<T> {
         let size = (x.size * y.size) as usize;
         let mut data = Vec::with_capacity(size);
         data.resize_with(size, Default::default);
-        Self { data, x, y }
+        Self {
+            data: Store::Dense(data),
+            x,
+            y,
+            topology: Topology::Growable,
+        }
+    }
+
+    // Fixed size Map from (x1, y1) to (x2, y2) inclusive
+    fn fixed((x1, y1): (isize, isize), (x2, y2): (isize, isize), topology: Topology) -> Self {
+        let x = Plane::fixed(x1, x2);
+        let y = Plane::fixed(y1, y2);
+        let data = vec![T::default(); (x.size * y.size) as usize];
+        Self {
+            data: Store::Dense(data),
+            x,
+            y,
+            topology,
+        }
+    }
+
+    /// Map from (x1, y1) to (x2, y2) inclusive which never grows, see try_write
+    pub fn bounded(from: (isize, isize), to: (isize, isize)) -> Self {
+        Self::fixed(from, to, Topology::Bounded)
+    }
+
+    /// Map from (x1, y1) to (x2, y2) inclusive where reads and writes beyond any edge wrap around
+    /// to the opposite edge
+    pub fn torus(from: (isize, isize), to: (isize, isize)) -> Self {
+        Self::fixed(from, to, Topology::Torus)
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    // The position actually meant by (x, y), which differs only for a Torus
+    fn normalise(&self, x: isize, y: isize) -> (isize, isize) {
+        match self.topology {
+            Topology::Torus => (self.x.wrap(x), self.y.wrap(y)),
+            _ => (x, y),
+        }
     }
 
     pub fn new() -> Self {
         Self::rect((-8, -8), (8, 8))
     }
 
+    /// Map which only stores the areas which are written, in tiles, suitable for a few features
+    /// scattered across a vast expanse, the live area is empty until the first write. As in any
+    /// Map, positions in the live area which weren't written hold the default value, so iterating
+    /// or looking for the default value visits the whole expanse
+    pub fn sparse() -> Self {
+        Self {
+            data: Store::Sparse(HashMap::new()),
+            x: Plane::vacant(0, 0),
+            y: Plane::vacant(0, 0),
+            topology: Topology::Growable,
+        }
+    }
+
+    /// Whether this Map was made by sparse()
+    pub fn is_sparse(&self) -> bool {
+        matches!(self.data, Store::Sparse(_))
+    }
+
     fn inbound(&self, x: isize, y: isize) -> bool {
         x >= self.x.offset
             && x < (self.x.offset + self.x.size)
@@ -95,6 +256,9 @@ impl<T: Copy + Default> Map<T> {
 
     /// Grow Map by suitably expanding both planes and re-allocating, then copying
     fn grow(&mut self) {
+        let Store::Dense(data) = &self.data else {
+            return;
+        };
         let new_x = self.x.expand();
         let new_y = self.y.expand();
 
@@ -110,25 +274,22 @@ impl<T: Copy + Default> Map<T> {
             for x in 0..self.x.size {
                 let from = y * self.x.size + x;
                 let dest = (y + off_y) * new_x.size + (x + off_x);
-                new_data[dest as usize] = self.data[from as usize];
+                new_data[dest as usize] = data[from as usize];
             }
         }
 
-        self.data = new_data;
+        self.data = Store::Dense(new_data);
         self.x = new_x;
         self.y = new_y;
     }
 
     fn include(&mut self, x: isize, y: isize) {
-        if x < self.x.start {
-            self.x.start = x;
-        } else if x > self.x.end {
-            self.x.end = x;
-        }
-        if y < self.y.start {
-            self.y.start = y;
-        } else if y > self.y.end {
-            self.y.end = y;
+        self.x.start = self.x.start.min(x);
+        self.x.end = self.x.end.max(x);
+        self.y.start = self.y.start.min(y);
+        self.y.end = self.y.end.max(y);
+        if self.is_sparse() {
+            return;
         }
         if !self.inbound(x, y) {
             self.grow();
@@ -154,9 +315,7 @@ impl<T: Copy + Default> Map<T> {
     }
 
     fn noitisop(&self, i: usize) -> (isize, isize) {
-        let y = self.y.offset + (i as isize / self.x.size);
-        let x = self.x.offset + (i as isize % self.x.size);
-        (x, y)
+        coordinates(&self.x, &self.y, i)
     }
 
     fn position(&self, x: isize, y: isize) -> usize {
@@ -164,51 +323,412 @@ impl<T: Copy + Default> Map<T> {
         posn as usize
     }
 
-    /// Write to (x, y) in the Map, this will grow the map automatically
+    /// Write to (x, y) in the Map, this will grow the map automatically unless it has a fixed
+    /// size, panics beyond the edges of a Bounded Map, see try_write
     pub fn write(&mut self, x: isize, y: isize, value: T) {
+        if let Err(e) = self.try_write(x, y, value) {
+            panic!("{e}");
+        }
+    }
+
+    /// Write to (x, y) in the Map, or fail if that's beyond the edges of a Bounded Map
+    pub fn try_write(&mut self, x: isize, y: isize, value: T) -> Result<(), OutOfBounds> {
+        let (x, y) = self.normalise(x, y);
+        if self.topology == Topology::Bounded && !self.inbound(x, y) {
+            return Err(OutOfBounds { x, y });
+        }
         self.include(x, y);
         let posn = self.position(x, y);
-        self.data[posn] = value;
+        match &mut self.data {
+            Store::Dense(data) => data[posn] = value,
+            Store::Sparse(tiles) => {
+                let (t, k) = tile(x, y);
+                let tile = tiles
+                    .entry(t)
+                    .or_insert_with(|| Box::new([T::default(); (TILE * TILE) as usize]));
+                tile[k] = value;
+            }
+        }
+        Ok(())
     }
 
     /// Reads an (x, y) position on the Map, but can be None if that position wasn't yet mapped
     /// Use or(value) or or_else(function) if appropriate
     pub fn read(&self, x: isize, y: isize) -> Option<T> {
-        if self.inbound(x, y) {
-            Some(self.data[self.position(x, y)])
-        } else {
-            None
+        let (x, y) = self.normalise(x, y);
+        match &self.data {
+            Store::Dense(data) if self.inbound(x, y) => Some(data[self.position(x, y)]),
+            Store::Dense(_) => None,
+            Store::Sparse(tiles) => {
+                let (t, k) = tile(x, y);
+                match tiles.get(&t) {
+                    Some(tile) => Some(tile[k]),
+                    None => self.live((x, y)).then(T::default),
+                }
+            }
         }
     }
 
-    /// Count how many of the mapped positions match the predicate
-    pub fn count<P>(&self, predicate: P) -> usize
+    /// Count how many of the mapped positions in the live area match the predicate
+    pub fn count<P>(&self, mut predicate: P) -> usize
     where
         P: FnMut(&&T) -> bool,
     {
-        self.data.iter().filter(predicate).count()
+        match &self.data {
+            Store::Dense(data) => (self.y.start..=self.y.end)
+                .filter(|&y| self.inbound(self.x.start, y) && self.inbound(self.x.end, y))
+                .map(|y| {
+                    let row = self.position(self.x.start, y)..=self.position(self.x.end, y);
+                    data[row].iter().filter(&mut predicate).count()
+                })
+                .sum(),
+            Store::Sparse(tiles) => {
+                let mut written = 0;
+                let mut n = 0;
+                for (&t, tile) in tiles.iter() {
+                    for value in (0..tile.len())
+                        .filter(|&k| self.live(untile(t, k)))
+                        .map(|k| &tile[k])
+                    {
+                        written += 1;
+                        if predicate(&value) {
+                            n += 1;
+                        }
+                    }
+                }
+                // Live positions in tiles which were never written hold the default value
+                if predicate(&&T::default()) {
+                    n += self.x.span() * self.y.span() - written;
+                }
+                n
+            }
+        }
+    }
+
+    /// Whether (x, y) is within the live area, the range of positions which were written
+    fn live(&self, (x, y): (isize, isize)) -> bool {
+        (self.x.start..=self.x.end).contains(&x) && (self.y.start..=self.y.end).contains(&y)
+    }
+
+    // Like read, but spare capacity outside the live area counts as unmapped
+    fn lookup(&self, (x, y): (isize, isize)) -> Option<T> {
+        self.live((x, y)).then(|| self.read(x, y)).flatten()
     }
 
-    /// Obtain a Vec of (x, y) positions matching the predicate
+    /// Every mapped position in the live area with its value, in order by row then column
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), T)> + '_ {
+        (self.y.start..=self.y.end).flat_map(move |y| self.row(y))
+    }
+
+    /// Every mapped position in the live area with a mutable reference to its value, in order by
+    /// row then column for a dense Map, but for a sparse Map only positions in tiles which were
+    /// written, in no particular order
+    pub fn iter_mut(&mut self) -> Box<dyn Iterator<Item = ((isize, isize), &mut T)> + '_> {
+        let (px, py) = (self.x, self.y);
+        let live = move |(x, y): (isize, isize)| {
+            (px.start..=px.end).contains(&x) && (py.start..=py.end).contains(&y)
+        };
+        match &mut self.data {
+            Store::Dense(data) => Box::new(
+                data.iter_mut()
+                    .enumerate()
+                    .map(move |(i, value)| (coordinates(&px, &py, i), value))
+                    .filter(move |&(posn, _)| live(posn)),
+            ),
+            Store::Sparse(tiles) => Box::new(
+                tiles
+                    .iter_mut()
+                    .flat_map(|(&t, tile)| {
+                        tile.iter_mut()
+                            .enumerate()
+                            .map(move |(k, value)| (untile(t, k), value))
+                    })
+                    .filter(move |&(posn, _)| live(posn)),
+            ),
+        }
+    }
+
+    /// Mapped positions in row y of the live area with their values, in order
+    pub fn row(&self, y: isize) -> impl Iterator<Item = ((isize, isize), T)> + '_ {
+        let xs = if (self.y.start..=self.y.end).contains(&y) {
+            self.x.start..=self.x.end
+        } else {
+            RangeInclusive::new(1, 0)
+        };
+        xs.filter_map(move |x| Some(((x, y), self.read(x, y)?)))
+    }
+
+    /// Mapped positions in column x of the live area with their values, in order
+    pub fn column(&self, x: isize) -> impl Iterator<Item = ((isize, isize), T)> + '_ {
+        let ys = if (self.x.start..=self.x.end).contains(&x) {
+            self.y.start..=self.y.end
+        } else {
+            RangeInclusive::new(1, 0)
+        };
+        ys.filter_map(move |y| Some(((x, y), self.read(x, y)?)))
+    }
+
+    /// The four positions orthogonally adjacent to (x, y) with their values, or None if they
+    /// weren't mapped, use filter_map to skip those, in order by row then column, on a Torus the
+    /// positions are wrapped
+    pub fn neighbours4(
+        &self,
+        x: isize,
+        y: isize,
+    ) -> impl Iterator<Item = ((isize, isize), Option<T>)> + '_ {
+        const FOUR: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+        FOUR.iter()
+            .map(move |(dx, dy)| self.normalise(x + dx, y + dy))
+            .map(|posn| (posn, self.lookup(posn)))
+    }
+
+    /// The eight positions orthogonally or diagonally adjacent to (x, y) with their values, or
+    /// None if they weren't mapped, use filter_map to skip those, in order by row then column
+    pub fn neighbours8(
+        &self,
+        x: isize,
+        y: isize,
+    ) -> impl Iterator<Item = ((isize, isize), Option<T>)> + '_ {
+        const EIGHT: [(isize, isize); 8] = [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+        EIGHT
+            .iter()
+            .map(move |(dx, dy)| self.normalise(x + dx, y + dy))
+            .map(|posn| (posn, self.lookup(posn)))
+    }
+
+    /// Obtain a Vec of (x, y) positions in the live area matching the predicate, in order by row
+    /// then column
     pub fn find<P>(&self, predicate: P) -> Vec<(isize, isize)>
     where
         P: Fn(T) -> bool,
     {
         let mut v = Vec::new();
 
-        for (i, &value) in self.data.iter().enumerate() {
-            if predicate(value) {
-                let (x, y) = self.noitisop(i);
-                v.push((x, y));
+        match &self.data {
+            Store::Dense(data) => {
+                for (i, &value) in data.iter().enumerate() {
+                    let posn = self.noitisop(i);
+                    if self.live(posn) && predicate(value) {
+                        v.push(posn);
+                    }
+                }
+            }
+            // Every live position might match, not just those in tiles which were written
+            Store::Sparse(_) if predicate(T::default()) => {
+                v.extend(
+                    self.iter()
+                        .filter(|&(_, value)| predicate(value))
+                        .map(|(posn, _)| posn),
+                );
+            }
+            Store::Sparse(tiles) => {
+                for (&t, tile) in tiles.iter() {
+                    for (k, &value) in tile.iter().enumerate() {
+                        let posn = untile(t, k);
+                        if self.live(posn) && predicate(value) {
+                            v.push(posn);
+                        }
+                    }
+                }
+                v.sort_unstable_by_key(|&(x, y)| (y, x));
             }
         }
         v
     }
+
+    /// Smallest area containing every position in the live area matching the predicate, as
+    /// ranges of x and y, or None if nothing matches
+    pub fn bounding_box_of<P>(&self, predicate: P) -> Option<Bounds>
+    where
+        P: Fn(T) -> bool,
+    {
+        let found = self.find(predicate);
+        let (xs, ys): (Vec<isize>, Vec<isize>) = found.into_iter().unzip();
+        let x = *xs.iter().min()?..=*xs.iter().max()?;
+        let y = *ys.first()?..=*ys.last()?;
+        Some((x, y))
+    }
+
+    /// Just this area of the Map, as a new growable Map whose live area is exactly x by y,
+    /// positions which weren't mapped here are left as the default value, if either range is
+    /// empty so is the new Map
+    pub fn crop(&self, x: RangeInclusive<isize>, y: RangeInclusive<isize>) -> Self {
+        if x.is_empty() || y.is_empty() {
+            return Self {
+                data: match self.data {
+                    Store::Dense(_) => Store::Dense(Vec::new()),
+                    Store::Sparse(_) => Store::Sparse(HashMap::new()),
+                },
+                x: Plane::vacant(0, 0),
+                y: Plane::vacant(0, 0),
+                topology: Topology::Growable,
+            };
+        }
+        let mut map = Self {
+            data: Store::Sparse(HashMap::new()),
+            x: Plane::fixed(*x.start(), *x.end()),
+            y: Plane::fixed(*y.start(), *y.end()),
+            topology: Topology::Growable,
+        };
+        match &self.data {
+            Store::Dense(_) => {
+                map.data = Store::Dense(vec![T::default(); (map.x.size * map.y.size) as usize]);
+                for row in y {
+                    for col in x.clone() {
+                        if let Some(value) = self.read(col, row) {
+                            map.write(col, row, value);
+                        }
+                    }
+                }
+            }
+            Store::Sparse(tiles) => {
+                for (&t, tile) in tiles.iter() {
+                    for (k, &value) in tile.iter().enumerate() {
+                        let (col, row) = untile(t, k);
+                        if x.contains(&col) && y.contains(&row) {
+                            map.write(col, row, value);
+                        }
+                    }
+                }
+            }
+        }
+        map
+    }
+
+    /// Release any space allocated beyond the live area, such as that left behind by growing,
+    /// a Map with a fixed size is already as small as it can be
+    pub fn shrink_to_fit(&mut self) {
+        if let Store::Sparse(tiles) = &mut self.data {
+            tiles.shrink_to_fit();
+        } else if self.topology == Topology::Growable {
+            *self = self.crop(self.x.start..=self.x.end, self.y.start..=self.y.end);
+        }
+    }
+
+    /// Picture of the live area, one pixel per position coloured by the palette, in the same
+    /// layout as Display, see image::Frames to record a simulation
+    pub fn image<F>(&self, palette: F) -> Image
+    where
+        F: Fn(T) -> Rgb,
+    {
+        let mut pixels = Vec::new();
+        for y in self.y.start..=self.y.end {
+            for x in self.x.start..=self.x.end {
+                pixels.push(palette(self.read(x, y).unwrap_or_default()));
+            }
+        }
+        Image::new(self.x.span(), self.y.span(), pixels)
+    }
+
+    // Empty Map with the same shape, backing store and topology as this one
+    fn blank<U: Copy + Default>(&self) -> Map<U> {
+        let data = match &self.data {
+            Store::Dense(data) => Store::Dense(vec![U::default(); data.len()]),
+            Store::Sparse(_) => Store::Sparse(HashMap::new()),
+        };
+        Map {
+            data,
+            x: self.x,
+            y: self.y,
+            topology: self.topology,
+        }
+    }
+
+    // Breadth first from the sources, recording in field the mark for how far away each position
+    // reached is, positions already recorded are never revisited
+    fn breadth<P, M>(
+        &self,
+        sources: &[(isize, isize)],
+        passable: &mut P,
+        field: &mut Map<Option<u32>>,
+        mark: M,
+    ) where
+        P: FnMut(T, T) -> bool,
+        M: Fn(u32) -> u32,
+    {
+        let mut todo = Vec::new();
+        for &(x, y) in sources {
+            let posn = self.normalise(x, y);
+            if self.lookup(posn).is_some() && field.read(posn.0, posn.1).flatten().is_none() {
+                field.write(posn.0, posn.1, Some(mark(0)));
+                todo.push(posn);
+            }
+        }
+        let mut dist = 0;
+        while !todo.is_empty() {
+            dist += 1;
+            let mut next = Vec::new();
+            for (x, y) in todo {
+                let here = self.read(x, y).expect("Only mapped positions are visited");
+                for ((nx, ny), there) in self.neighbours4(x, y) {
+                    let Some(there) = there else {
+                        continue;
+                    };
+                    if field.read(nx, ny).flatten().is_none() && passable(here, there) {
+                        field.write(nx, ny, Some(mark(dist)));
+                        next.push((nx, ny));
+                    }
+                }
+            }
+            todo = next;
+        }
+    }
+
+    /// Every position reachable from start by orthogonal steps, where passable(from, to) says
+    /// whether it's possible to step from a position with the value from to one with the value
+    /// to, in order by row then column
+    pub fn flood<P>(&self, start: (isize, isize), passable: P) -> Vec<(isize, isize)>
+    where
+        P: FnMut(T, T) -> bool,
+    {
+        self.distances(&[start], passable).find(|d| d.is_some())
+    }
+
+    /// Fewest orthogonal steps from any of the sources to each position, or None where that's
+    /// unreachable, see flood for passable, sources which weren't mapped are ignored
+    pub fn distances<P>(&self, sources: &[(isize, isize)], mut passable: P) -> Map<Option<u32>>
+    where
+        P: FnMut(T, T) -> bool,
+    {
+        let mut field = self.blank();
+        self.breadth(sources, &mut passable, &mut field, |dist| dist);
+        field
+    }
+
+    /// Label each mapped position with the number of its connected component, counting from 0
+    /// in order by row then column of the first position found, see flood for passable which
+    /// should usually be symmetric, e.g. |a, b| a == b labels regions of the same value
+    pub fn components<P>(&self, mut passable: P) -> Map<Option<u32>>
+    where
+        P: FnMut(T, T) -> bool,
+    {
+        let mut labels = self.blank();
+        let mut label = 0;
+        for (posn, _) in self.iter() {
+            if labels.read(posn.0, posn.1).flatten().is_none() {
+                self.breadth(&[posn], &mut passable, &mut labels, |_| label);
+                label += 1;
+            }
+        }
+        labels
+    }
 }
 
 use std::fmt;
 impl<T: fmt::Debug + Copy + Default> fmt::Debug for Map<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Store::Sparse(tiles) = &self.data {
+            f.write_fmt(format_args!("{} tiles ", tiles.len()))?;
+        }
         f.write_fmt(format_args!(
             "x: [ {} {} ... {} {} ] ",
             self.x.offset,
@@ -223,14 +743,9 @@ impl<T: fmt::Debug + Copy + Default> fmt::Debug for Map<T> {
             self.y.end,
             self.y.offset + self.y.size
         ))?;
-        let from_y = self.y.start - self.y.offset;
-        let from_x = self.x.start - self.x.offset;
-        let to_y = self.y.size - (self.y.offset + self.y.size - self.y.end) + 1;
-        let to_x = self.x.size - (self.x.offset + self.x.size - self.x.end) + 1;
-        for row in from_y..to_y {
-            for col in from_x..to_x {
-                let posn = row * self.x.size + col;
-                let s = format!("{:?}", self.data[posn as usize]);
+        for y in self.y.start..=self.y.end {
+            for x in self.x.start..=self.x.end {
+                let s = format!("{:?}", self.read(x, y).unwrap_or_default());
                 f.write_str(&s)?;
             }
             f.write_str("\n")?;
@@ -241,14 +756,9 @@ impl<T: fmt::Debug + Copy + Default> fmt::Debug for Map<T> {
 
 impl<T: fmt::Display + Copy + Default> fmt::Display for Map<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let from_y = self.y.start - self.y.offset;
-        let from_x = self.x.start - self.x.offset;
-        let to_y = self.y.size - (self.y.offset + self.y.size - self.y.end) + 1;
-        let to_x = self.x.size - (self.x.offset + self.x.size - self.x.end) + 1;
-        for row in from_y..to_y {
-            for col in from_x..to_x {
-                let posn = row * self.x.size + col;
-                let s = format!("{}", self.data[posn as usize]);
+        for y in self.y.start..=self.y.end {
+            for x in self.x.start..=self.x.end {
+                let s = format!("{}", self.read(x, y).unwrap_or_default());
                 f.write_str(&s)?;
             }
             f.write_str("\n")?;
@@ -257,23 +767,362 @@ impl<T: fmt::Display + Copy + Default> fmt::Display for Map<T> {
     }
 }
 
-use std::convert::Infallible;
+use crate::image::{Image, Rgb};
+use crate::ParseError;
 use std::str::FromStr;
+
+/// Ranges of x and y, from the first to the last inclusive, covered by part of a Map
+pub type Bounds = (RangeInclusive<isize>, RangeInclusive<isize>);
+
+/// Where each marker character was found while parsing a Map
+pub type Markers = HashMap<char, (isize, isize)>;
+
+// A bad character in the row and column (counting from 0) of the map text
+fn bad(row: usize, col: usize, ch: char, reason: impl fmt::Display) -> ParseError {
+    ParseError {
+        line: row + 1,
+        column: col + 1,
+        text: ch.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+impl<T: Copy + Default> Map<T> {
+    /// Map from text with one character per cell, the first character of the first line is at
+    /// (0, 0), the closure makes each cell or explains why it can't
+    pub fn parse_with<F, E>(s: &str, cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Result<T, E>,
+        E: fmt::Display,
+    {
+        let (map, _) = Self::parse_marked(s, &[], cell)?;
+        Ok(map)
+    }
+
+    /// Like parse_with, but also find where each of the marker characters is, such as the start
+    /// and end of a maze, the closure still decides what is mapped there, each marker may appear
+    /// at most once
+    pub fn parse_marked<F, E>(
+        s: &str,
+        markers: &[char],
+        mut cell: F,
+    ) -> Result<(Self, Markers), ParseError>
+    where
+        F: FnMut(char) -> Result<T, E>,
+        E: fmt::Display,
+    {
+        let mut map = Self::rect((0, 0), (0, 0));
+        let mut found = HashMap::new();
+        for (row, line) in s.lines().enumerate() {
+            for (col, ch) in line.chars().enumerate() {
+                let posn = (col as isize, row as isize);
+                if markers.contains(&ch) && found.insert(ch, posn).is_some() {
+                    return Err(bad(row, col, ch, "marker appears more than once"));
+                }
+                let item = cell(ch).map_err(|e| bad(row, col, ch, e))?;
+                map.write(posn.0, posn.1, item);
+            }
+        }
+        Ok((map, found))
+    }
+}
+
+/// Parse with a cell type which can be made from (or tried from) a char, see parse_with
 impl<T> FromStr for Map<T>
 where
-    char: Into<T>,
-    T: Copy + Default,
+    T: TryFrom<char> + Copy + Default,
+    T::Error: fmt::Display,
 {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut map = Self::rect((0, 0), (0, 0));
-        for (row, line) in s.lines().enumerate() {
-            for (col, ch) in line.chars().enumerate() {
-                let item = ch.into();
-                map.write(col as isize, row as isize, item);
+        Self::parse_with(s, T::try_from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::map::Map;
+    use std::ops::RangeInclusive;
+
+    #[test]
+    fn sparse() {
+        let mut map: Map<char> = Map::sparse();
+        assert!(map.is_sparse());
+        assert_eq!(map.to_string(), "");
+        assert_eq!(map.iter().count(), 0);
+        map.write(1_000_000, 1_000_000, '#');
+        assert_eq!(map.to_string(), "#\n");
+        assert_eq!(map.count(|_| true), 1);
+        assert_eq!(map.image(|_| [0, 0, 0]).width(), 1);
+
+        let mut map: Map<char> = Map::sparse();
+        map.write(-3_000_000, 2_000_000, '#');
+        map.write(4_000_000, -17, '#');
+        map.write(4_000_001, -17, 'S');
+        assert_eq!(map.read(-3_000_000, 2_000_000), Some('#'));
+        assert_eq!(map.read(-2_999_999, 2_000_000), Some('\0'));
+        assert_eq!(map.read(0, 0), Some('\0'));
+        assert_eq!(map.read(0, -18), None);
+        assert_eq!(map.count(|&&ch| ch == '#'), 2);
+        assert_eq!(
+            map.find(|ch| ch != '\0'),
+            [(4_000_000, -17), (4_000_001, -17), (-3_000_000, 2_000_000)]
+        );
+        assert_eq!(map.x(), -3_000_000..4_000_001);
+        assert_eq!(map.y(), -17..2_000_000);
+    }
+
+    #[derive(Copy, Clone, Debug, Default, PartialEq)]
+    enum Cell {
+        #[default]
+        Open,
+        Wall,
+    }
+
+    impl TryFrom<char> for Cell {
+        type Error = &'static str;
+
+        fn try_from(ch: char) -> Result<Self, Self::Error> {
+            match ch {
+                '.' => Ok(Cell::Open),
+                '#' => Ok(Cell::Wall),
+                _ => Err("not a map cell"),
             }
         }
-        Ok(map)
+    }
+
+    #[test]
+    fn parse() {
+        let map: Map<Cell> = "#..\n.##\n".parse().unwrap();
+        assert_eq!(map.read(2, 1), Some(Cell::Wall));
+        assert_eq!(map.count(|&&c| c == Cell::Wall), 3);
+
+        let err = "#..\n.#?\n".parse::<Map<Cell>>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.text, "?");
+
+        let (map, found) = Map::parse_marked("S.#\n..E\n", &['S', 'E'], |ch| match ch {
+            'S' | 'E' => Ok(Cell::Open),
+            _ => Cell::try_from(ch),
+        })
+        .unwrap();
+        assert_eq!(found[&'S'], (0, 0));
+        assert_eq!(found[&'E'], (2, 1));
+        assert_eq!(map.read(2, 1), Some(Cell::Open));
+
+        let err = Map::parse_marked("S.S\n", &['S'], |_| Ok::<_, &str>(Cell::Open)).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+    }
+
+    #[test]
+    fn topology() {
+        use crate::map::{OutOfBounds, Topology};
+
+        let mut map: Map<u8> = Map::bounded((0, 0), (3, 2));
+        assert_eq!(map.topology(), Topology::Bounded);
+        assert_eq!(map.try_write(3, 2, 7), Ok(()));
+        assert_eq!(map.try_write(4, 2, 7), Err(OutOfBounds { x: 4, y: 2 }));
+        assert_eq!(map.try_write(0, -1, 7), Err(OutOfBounds { x: 0, y: -1 }));
+        assert_eq!(map.read(4, 2), None);
+        assert_eq!(map.read(0, 0), Some(0));
+        assert_eq!(map.iter().count(), 12);
+
+        let mut map: Map<u8> = Map::torus((0, 0), (3, 2));
+        map.write(5, -1, 9);
+        assert_eq!(map.read(1, 2), Some(9));
+        assert_eq!(map.read(-3, 5), Some(9));
+        assert_eq!(map.find(|n| n == 9), [(1, 2)]);
+        let wrapped: Vec<_> = map.neighbours4(1, 0).collect();
+        assert_eq!(wrapped[0], ((1, 2), Some(9)));
+        assert_eq!(map.x(), 0..3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bounded_write() {
+        let mut map: Map<u8> = Map::bounded((0, 0), (3, 2));
+        map.write(0, 3, 1);
+    }
+
+    #[test]
+    fn search() {
+        let map: Map<char> = "..#..\n.##.#\n...#.\n".parse().unwrap();
+        let open = |_, to| to == '.';
+        let dist = map.distances(&[(0, 0)], open);
+        assert_eq!(dist.read(4, 0), Some(None));
+        assert_eq!(dist.read(2, 2), Some(Some(4)));
+        assert_eq!(dist.read(3, 1), Some(None));
+        let both = map.distances(&[(0, 0), (4, 0)], open);
+        assert_eq!(both.read(3, 1), Some(Some(2)));
+
+        assert_eq!(map.flood((4, 2), open), [(4, 2)]);
+        assert_eq!(map.flood((0, 2), open).len(), 6);
+
+        let labels = map.components(|a, b| a == b);
+        assert_eq!(labels.read(0, 0), Some(Some(0)));
+        assert_eq!(labels.read(2, 0), Some(Some(1)));
+        assert_eq!(labels.read(3, 0), Some(Some(2)));
+        assert_eq!(labels.read(4, 1), Some(Some(3)));
+        assert_eq!(labels.read(2, 1), Some(Some(1)));
+        assert_eq!(labels.read(4, 2), Some(Some(5)));
+        assert_eq!(labels.find(|n| n == Some(4)), [(3, 2)]);
+
+        let ring: Map<char> = Map::parse_with("#.#\n...\n#.#\n", Ok::<_, &str>).unwrap();
+        let mut torus: Map<char> = Map::torus((0, 0), (2, 2));
+        for ((x, y), ch) in ring.iter() {
+            torus.write(x, y, ch);
+        }
+        let dist = torus.distances(&[(1, 0)], open);
+        assert_eq!(dist.read(0, 1), Some(Some(2)));
+        assert_eq!(dist.read(1, 2), Some(Some(1)));
+    }
+
+    #[test]
+    fn shrink() {
+        let mut map: Map<char> = Map::new();
+        map.write(3, 4, '#');
+        map.write(-2, 7, '#');
+        map.write(30, 0, '.');
+        assert_eq!(map.count(|&&ch| ch == '\0'), 33 * 8 - 3);
+        assert_eq!(map.find(|ch| ch == '\0').len(), 33 * 8 - 3);
+        let (x, y) = map.bounding_box_of(|ch| ch == '#').unwrap();
+        assert_eq!((x.clone(), y.clone()), (-2..=3, 4..=7));
+        assert_eq!(map.bounding_box_of(|ch| ch == 'X'), None);
+
+        let crop = map.crop(x, y);
+        assert_eq!(crop.count(|_| true), 24);
+        assert_eq!(crop.read(3, 4), Some('#'));
+        assert_eq!(crop.read(30, 0), None);
+        assert_eq!(crop.find(|ch| ch == '#'), [(3, 4), (-2, 7)]);
+
+        let mut empty = map.crop(RangeInclusive::new(5, 4), 0..=7);
+        assert_eq!(empty.count(|_| true), 0);
+        assert_eq!(empty.to_string(), "");
+        empty.write(-20, 30, '#');
+        assert_eq!(empty.read(-20, 30), Some('#'));
+        assert_eq!(empty.to_string(), "#\n");
+
+        let before = map.to_string();
+        map.shrink_to_fit();
+        assert_eq!(map.to_string(), before);
+        assert_eq!(map.read(-3, 7), None);
+        assert_eq!(map.read(30, 7), Some('\0'));
+        map.write(-3, 8, '#');
+        assert_eq!(map.count(|&&ch| ch == '#'), 3);
+
+        let mut unallocated: Map<char> = Map::rect((0, 0), (0, 0));
+        assert_eq!(unallocated.count(|_| true), 0);
+        unallocated.write(0, 0, '#');
+        assert_eq!(unallocated.count(|_| true), 1);
+
+        let mut sparse: Map<char> = Map::sparse();
+        sparse.write(1_000_000, 0, '#');
+        sparse.write(-1_000_000, 3, '#');
+        let crop = sparse.crop(999_990..=1_000_010, -1..=1);
+        assert!(crop.is_sparse());
+        let empty = sparse.crop(0..=3, RangeInclusive::new(1, 0));
+        assert!(empty.is_sparse());
+        assert_eq!(empty.iter().count(), 0);
+        assert_eq!(crop.find(|ch| ch == '#'), [(1_000_000, 0)]);
+        assert_eq!(sparse.count(|&&ch| ch == '\0'), 2_000_001 * 4 - 2);
+        assert_eq!(sparse.count(|&&ch| ch == '#'), 2);
+    }
+
+    #[test]
+    fn image() {
+        let map: Map<char> = "#..\n.S#\n".parse().unwrap();
+        let image = map.image(|ch| match ch {
+            '#' => [0, 0, 0],
+            'S' => [255, 0, 0],
+            _ => [255, 255, 255],
+        });
+        assert_eq!((image.width(), image.height()), (3, 2));
+        assert_eq!(image.pixel(0, 0), [0, 0, 0]);
+        assert_eq!(image.pixel(1, 1), [255, 0, 0]);
+        assert_eq!(image.pixel(2, 0), [255, 255, 255]);
+
+        let mut sparse: Map<u8> = Map::sparse();
+        sparse.write(-40, 3, 9);
+        sparse.write(40, 2, 9);
+        let image = sparse.image(|n| [n, n, n]);
+        assert_eq!((image.width(), image.height()), (81, 2));
+        assert_eq!(image.pixel(0, 1), [9, 9, 9]);
+        assert_eq!(image.pixel(80, 0), [9, 9, 9]);
+        assert_eq!(image.pixel(40, 0), [0, 0, 0]);
+    }
+
+    #[test]
+    fn iterate() {
+        let mut map: Map<char> = "#..\n.S#\n".parse().unwrap();
+        let cells: Vec<((isize, isize), char)> = map.iter().collect();
+        assert_eq!(cells.len(), 6);
+        assert_eq!(cells[4], ((1, 1), 'S'));
+        let row: String = map.row(1).map(|(_, ch)| ch).collect();
+        assert_eq!(row, ".S#");
+        let column: String = map.column(2).map(|(_, ch)| ch).collect();
+        assert_eq!(column, ".#");
+        assert_eq!(map.row(7).count(), 0);
+
+        let hashes = map.neighbours8(1, 0).filter(|&(_, ch)| ch == Some('#'));
+        assert_eq!(
+            hashes.map(|(posn, _)| posn).collect::<Vec<_>>(),
+            [(0, 0), (2, 1)]
+        );
+        let four: Vec<_> = map.neighbours4(0, 0).collect();
+        assert_eq!(four[0].1, None);
+        assert_eq!(four[3], ((0, 1), Some('.')));
+        assert_eq!(map.neighbours4(0, 0).filter_map(|(_, ch)| ch).count(), 2);
+
+        for (_, ch) in map.iter_mut() {
+            if *ch == '.' {
+                *ch = ' ';
+            }
+        }
+        assert_eq!(map.to_string(), "#  \n S#\n");
+
+        let mut sparse: Map<char> = Map::sparse();
+        sparse.write(100, 5, 'x');
+        sparse.write(-100, 5, 'y');
+        let cells: Vec<_> = sparse.iter().filter(|&(_, ch)| ch != '\0').collect();
+        assert_eq!(cells, [((-100, 5), 'y'), ((100, 5), 'x')]);
+        for (_, ch) in sparse.iter_mut() {
+            *ch = 'z';
+        }
+        assert_eq!(sparse.read(100, 5), Some('z'));
+        assert_eq!(sparse.read(-100, 5), Some('z'));
+    }
+
+    #[test]
+    fn backends() {
+        let text = "#..\n.S#\n";
+        let dense: Map<char> = text.parse().unwrap();
+        let mut sparse: Map<char> = Map::sparse();
+        for (y, line) in text.lines().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                sparse.write(x as isize, y as isize, ch);
+            }
+        }
+        assert_eq!(dense.to_string(), text);
+        assert_eq!(sparse.to_string(), text);
+        assert_eq!(dense.find(|ch| ch == '#'), sparse.find(|ch| ch == '#'));
+        assert_eq!(
+            dense.count(|&&ch| ch == '.'),
+            sparse.count(|&&ch| ch == '.')
+        );
+
+        // Positions which weren't written hold the default value whichever the backend
+        let mut dense: Map<u8> = Map::new();
+        let mut sparse: Map<u8> = Map::sparse();
+        for map in [&mut dense, &mut sparse] {
+            map.write(0, 0, 1);
+            map.write(100, 0, 1);
+        }
+        assert_eq!(dense.count(|&&v| v == 0), 99);
+        assert_eq!(sparse.count(|&&v| v == 0), 99);
+        assert_eq!(sparse.find(|v| v == 0), dense.find(|v| v == 0));
+        assert_eq!(sparse.iter().count(), 101);
+        assert_eq!(sparse.read(50, 0), Some(0));
+        assert_eq!(sparse.to_string(), dense.to_string());
     }
 }
@@ -0,0 +1,384 @@
+//! Orderings and selections of items, these iterators lend out each ordering or selection in turn
+//! rather than allocating, so they're used with while let, e.g.
+//!
+//! ```
+//! use jungle::permute::Permutations;
+//!
+//! let mut items = [1, 2, 3];
+//! let mut orders = Permutations::new(&mut items);
+//! let mut count = 0;
+//! while let Some(order) = orders.next() {
+//!     assert_eq!(order.len(), 3);
+//!     count += 1;
+//! }
+//! assert_eq!(count, 6);
+//! ```
+
+use crate::{factorial, heap, permutations};
+
+/// Every permutation of a slice, in place, using Heap's Algorithm, so each permutation differs
+/// from the last by a single swap, the slice is left in its final permutation afterwards
+pub struct Permutations<'a, T> {
+    items: &'a mut [T],
+    n: usize,
+    total: usize,
+}
+
+impl<'a, T> Permutations<'a, T> {
+    /// Slices longer than 20 have more permutations than a usize can count, and more than could
+    /// ever be visited, so for those this just carries on as long as anybody asks
+    pub fn new(items: &'a mut [T]) -> Self {
+        let total = (2..=items.len()).try_fold(1usize, |p, k| p.checked_mul(k));
+        Self {
+            items,
+            n: 0,
+            total: total.unwrap_or(usize::MAX),
+        }
+    }
+
+    /// The next permutation, the first is the slice as it was given
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[T]> {
+        if self.n >= self.total {
+            return None;
+        }
+        heap(self.items, self.n);
+        self.n += 1;
+        Some(self.items)
+    }
+}
+
+/// Rearrange into the next lexicographically greater permutation, returning false (having
+/// rearranged into the first, sorted, permutation) if this was already the last one
+pub fn next_permutation<T: Ord>(items: &mut [T]) -> bool {
+    // Longest non-increasing suffix
+    let mut k = items.len();
+    while k > 1 && items[k - 2] >= items[k - 1] {
+        k -= 1;
+    }
+    if k <= 1 {
+        items.reverse();
+        return false;
+    }
+    let pivot = k - 2;
+    let mut swap = items.len() - 1;
+    while items[swap] <= items[pivot] {
+        swap -= 1;
+    }
+    items.swap(pivot, swap);
+    items[pivot + 1..].reverse();
+    true
+}
+
+/// Position of this arrangement of distinct items in the lexicographic order of all arrangements
+/// of them, via its Lehmer code, panics for more than 20 items since there are too many
+/// arrangements of those to count in a usize
+pub fn rank<T: Ord>(items: &[T]) -> usize {
+    let n = items.len();
+    assert!(
+        n <= 20,
+        "Too many arrangements of {n} items to rank in a usize"
+    );
+    let mut rank = 0;
+    for (k, item) in items.iter().enumerate() {
+        let smaller = items[k + 1..].iter().filter(|&other| other < item).count();
+        rank += smaller * permutations(n - 1 - k);
+    }
+    rank
+}
+
+/// Rearrange items, which should be in their first (sorted) arrangement, into the arrangement at
+/// this position in lexicographic order, so that afterwards rank(items) is rank, panics if there
+/// aren't that many arrangements
+pub fn unrank<T>(items: &mut [T], mut rank: usize) {
+    let n = items.len();
+    assert!(
+        factorial(n).is_none_or(|total| rank < total),
+        "There are fewer than {rank} arrangements of {n} items"
+    );
+    for k in 0..n {
+        let Some(f) = factorial(n - 1 - k) else {
+            continue; // The rank is too small to affect this item
+        };
+        let d = rank / f;
+        rank %= f;
+        items[k..=k + d].rotate_right(1);
+    }
+}
+
+/// Every ordered selection of k indices from 0..n, in lexicographic order
+pub struct KPermutations {
+    indices: Vec<usize>,
+    k: usize,
+    started: bool,
+    done: bool,
+}
+
+impl KPermutations {
+    pub fn new(n: usize, k: usize) -> Self {
+        Self {
+            indices: (0..n).collect(),
+            k,
+            started: false,
+            done: k > n,
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+        if self.started {
+            // Reversing the unselected indices makes them the last ordering with this prefix
+            self.indices[self.k..].reverse();
+            if !next_permutation(&mut self.indices) {
+                self.done = true;
+                return None;
+            }
+        }
+        self.started = true;
+        Some(&self.indices[..self.k])
+    }
+}
+
+/// Every selection of k distinct indices from 0..n, each in increasing order, in lexicographic
+/// order
+pub struct Combinations {
+    indices: Vec<usize>,
+    n: usize,
+    started: bool,
+    done: bool,
+}
+
+impl Combinations {
+    pub fn new(n: usize, k: usize) -> Self {
+        Self {
+            indices: (0..k).collect(),
+            n,
+            started: false,
+            done: k > n,
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+        if self.started {
+            let k = self.indices.len();
+            // Rightmost index which isn't already as large as it can be
+            let Some(p) = (0..k).rev().find(|&p| self.indices[p] < self.n - k + p) else {
+                self.done = true;
+                return None;
+            };
+            self.indices[p] += 1;
+            for q in p + 1..k {
+                self.indices[q] = self.indices[q - 1] + 1;
+            }
+        }
+        self.started = true;
+        Some(&self.indices)
+    }
+}
+
+/// Every selection of k indices from 0..n where an index may be selected more than once, each in
+/// non-decreasing order, in lexicographic order
+pub struct CombinationsWithReplacement {
+    indices: Vec<usize>,
+    n: usize,
+    started: bool,
+    done: bool,
+}
+
+impl CombinationsWithReplacement {
+    pub fn new(n: usize, k: usize) -> Self {
+        Self {
+            indices: vec![0; k],
+            n,
+            started: false,
+            done: n == 0 && k > 0,
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&[usize]> {
+        if self.done {
+            return None;
+        }
+        if self.started {
+            let Some(p) = self.indices.iter().rposition(|&i| i + 1 < self.n) else {
+                self.done = true;
+                return None;
+            };
+            let i = self.indices[p] + 1;
+            self.indices[p..].fill(i);
+        }
+        self.started = true;
+        Some(&self.indices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::permute::{
+        next_permutation, rank, unrank, Combinations, CombinationsWithReplacement, KPermutations,
+        Permutations,
+    };
+    use crate::{binomial, factorial, heap, permutations};
+    use std::collections::HashSet;
+
+    // Heap's Algorithm as usually written, with a counter for each position
+    fn reference(a: &mut [u8], steps: usize, mut visit: impl FnMut(&[u8])) {
+        let mut c = vec![0; a.len()];
+        visit(a);
+        let mut i = 1;
+        let mut done = 0;
+        while i < a.len() && done < steps {
+            if c[i] < i {
+                if i % 2 == 0 {
+                    a.swap(0, i);
+                } else {
+                    a.swap(c[i], i);
+                }
+                visit(a);
+                done += 1;
+                c[i] += 1;
+                i = 1;
+            } else {
+                c[i] = 0;
+                i += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn heap_matches_reference() {
+        for len in 1..=7 {
+            let mut expected = Vec::new();
+            let mut a: Vec<u8> = (0..len).collect();
+            reference(&mut a, usize::MAX, |p| expected.push(p.to_vec()));
+            assert_eq!(expected.len(), permutations(len as usize));
+
+            let mut b: Vec<u8> = (0..len).collect();
+            let mut orders = Permutations::new(&mut b);
+            let mut actual = Vec::new();
+            while let Some(p) = orders.next() {
+                actual.push(p.to_vec());
+            }
+            assert_eq!(actual, expected);
+            let distinct: HashSet<Vec<u8>> = actual.into_iter().collect();
+            assert_eq!(distinct.len(), permutations(len as usize));
+        }
+    }
+
+    #[test]
+    fn heap_eleven() {
+        // Just past the first swap into the last position of eleven
+        let steps = permutations(10) + 10;
+        let mut a: Vec<u8> = (0..11).collect();
+        let mut b = a.clone();
+        let mut n = 0;
+        reference(&mut a, steps, |p| {
+            heap(&mut b, n);
+            n += 1;
+            assert_eq!(p, b);
+        });
+        assert_ne!(b[10], 10);
+    }
+
+    #[test]
+    fn factorials() {
+        assert_eq!(factorial(21), None);
+        assert_eq!(binomial(4, 2), Some(6));
+        assert_eq!(binomial(4, 5), Some(0));
+        assert_eq!(binomial(62, 31), Some(465428353255261088));
+        assert_eq!(binomial(68, 34), None);
+        const CARDS: Option<usize> = binomial(52, 5);
+        assert_eq!(CARDS, Some(2598960));
+        assert_eq!(permutations(0), 1);
+        assert_eq!(permutations(10), 3628800);
+        assert_eq!(permutations(13), 6227020800);
+        assert_eq!(permutations(20), 2432902008176640000);
+    }
+
+    #[test]
+    fn lexicographic() {
+        let mut a = [1, 2, 2, 3];
+        let mut all = vec![a];
+        while next_permutation(&mut a) {
+            all.push(a);
+        }
+        assert_eq!(all.len(), 12);
+        assert!(all.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(a, [1, 2, 2, 3]);
+        let mut empty: [u8; 0] = [];
+        assert!(!next_permutation(&mut empty));
+    }
+
+    #[test]
+    fn ranks() {
+        let mut a = ['a', 'b', 'c', 'd', 'e'];
+        let mut n = 0;
+        loop {
+            assert_eq!(rank(&a), n);
+            let mut b = ['a', 'b', 'c', 'd', 'e'];
+            unrank(&mut b, n);
+            assert_eq!(a, b);
+            n += 1;
+            if !next_permutation(&mut a) {
+                break;
+            }
+        }
+        assert_eq!(n, 120);
+
+        let mut big: Vec<u8> = (0..25).collect();
+        unrank(&mut big, 3);
+        assert_eq!(big[22..], [23, 24, 22]);
+        let mut last: Vec<u8> = (0..20).collect();
+        unrank(&mut last, permutations(20) - 1);
+        assert!(last.windows(2).all(|w| w[0] > w[1]));
+        assert_eq!(rank(&last), permutations(20) - 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many arrangements")]
+    fn rank_too_many() {
+        let reversed: Vec<u8> = (0..21).rev().collect();
+        rank(&reversed);
+    }
+
+    fn collect(mut next: impl FnMut() -> Option<Vec<usize>>) -> Vec<Vec<usize>> {
+        let mut v = Vec::new();
+        while let Some(s) = next() {
+            v.push(s);
+        }
+        v
+    }
+
+    #[test]
+    fn selections() {
+        let mut k = KPermutations::new(4, 2);
+        let all = collect(|| k.next().map(<[usize]>::to_vec));
+        assert_eq!(all.len(), 12);
+        assert_eq!(all[..4], [vec![0, 1], vec![0, 2], vec![0, 3], vec![1, 0]]);
+        assert!(all.windows(2).all(|w| w[0] < w[1]));
+
+        let mut c = Combinations::new(5, 3);
+        let all = collect(|| c.next().map(<[usize]>::to_vec));
+        assert_eq!(all.len(), 10);
+        assert_eq!(all[0], [0, 1, 2]);
+        assert_eq!(all[9], [2, 3, 4]);
+
+        let mut r = CombinationsWithReplacement::new(3, 2);
+        let all = collect(|| r.next().map(<[usize]>::to_vec));
+        let expected = [[0, 0], [0, 1], [0, 2], [1, 1], [1, 2], [2, 2]];
+        assert_eq!(all, expected);
+
+        assert_eq!(Combinations::new(3, 4).next(), None);
+        assert_eq!(KPermutations::new(3, 0).next(), Some(&[][..]));
+        assert_eq!(Combinations::new(3, 0).next(), Some(&[][..]));
+        assert_eq!(CombinationsWithReplacement::new(0, 1).next(), None);
+    }
+}
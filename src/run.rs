@@ -0,0 +1,331 @@
+use jungle::answers::{self, Answers};
+use jungle::{Answer, Input, Part, Registry};
+
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+/// Parse one selection, which is all, or a day (4), or a range of days (1-10), optionally followed
+/// by a part (4b) or * for both parts (7*)
+fn selection(registry: &Registry, which: &str) -> Result<Vec<(u8, Part)>, String> {
+    let unavailable = || format!("{which} not available yet");
+
+    if which == "all" || which == "*" {
+        return Ok(registry.parts().collect());
+    }
+    let (days, parts) = match which.char_indices().last() {
+        Some((k, 'a' | 'b')) => (&which[..k], vec![which[k..].parse()?]),
+        Some((k, '*')) => (&which[..k], Part::BOTH.to_vec()),
+        _ => (which, Part::BOTH.to_vec()),
+    };
+    let (first, last) = match days.split_once('-') {
+        Some((first, last)) => (first, last),
+        None => (days, days),
+    };
+    let first: u8 = first.parse().map_err(|_| unavailable())?;
+    let last: u8 = last.parse().map_err(|_| unavailable())?;
+    if first > last {
+        return Err(format!("{which} is not a sensible range of days"));
+    }
+
+    let mut v = Vec::new();
+    for solver in registry.days() {
+        if (first..=last).contains(&solver.day()) {
+            v.extend(parts.iter().map(|&part| (solver.day(), part)));
+        }
+    }
+    if v.is_empty() {
+        return Err(unavailable());
+    }
+    Ok(v)
+}
+
+/// Every (day, part) chosen by the selections, in order by day and without duplicates
+pub fn select(registry: &Registry, which: &[String]) -> Result<Vec<(u8, Part)>, String> {
+    let mut v = Vec::new();
+    for which in which {
+        v.append(&mut selection(registry, which)?);
+    }
+    v.sort_unstable();
+    v.dedup();
+    Ok(v)
+}
+
+/// How an answer compared to the expected answer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail(Answer),
+    Missing,
+}
+
+impl Status {
+    fn label(&self) -> &'static str {
+        match self {
+            Status::Pass => "PASS",
+            Status::Fail(_) => "FAIL",
+            Status::Missing => "MISSING",
+        }
+    }
+}
+
+/// The outcome of solving one part of a day's puzzle
+pub struct Row {
+    pub day: u8,
+    pub part: Part,
+    pub outcome: Result<Answer, String>,
+    pub time: Duration,
+    pub status: Option<Status>,
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "Solver panicked".to_string()
+    }
+}
+
+/// Solve each part, timing how long the solver takes, a failure to read the input or a panic in
+/// the solver is reported in that row rather than stopping the run
+pub fn run(registry: &Registry, input: &Input, parts: &[(u8, Part)]) -> Vec<Row> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut rows = Vec::new();
+    let mut ctxt = None;
+    for &(day, part) in parts {
+        let solver = registry.get(day).expect("Only available days are selected");
+        if !matches!(ctxt, Some((d, _)) if d == day) {
+            ctxt = Some((day, input.read(day).map_err(|e| e.to_string())));
+        }
+        let Some((_, read)) = &ctxt else {
+            unreachable!("Contents were just read");
+        };
+
+        let (outcome, time) = match read {
+            Ok(ctxt) => {
+                let start = Instant::now();
+                let outcome = panic::catch_unwind(AssertUnwindSafe(|| solver.solve(part, ctxt)));
+                (outcome.map_err(panic_message), start.elapsed())
+            }
+            Err(e) => (Err(e.clone()), Duration::ZERO),
+        };
+        rows.push(Row {
+            day,
+            part,
+            outcome,
+            time,
+            status: None,
+        });
+    }
+
+    panic::set_hook(hook);
+    rows
+}
+
+/// Compare each answer with the expected answer for this input set, a part which failed to produce
+/// an answer at all has failed, even if no answer was expected, see success
+pub fn check(rows: &mut [Row], expected: &Answers, set: &str) {
+    for row in rows {
+        let status = match (expected.get(set, row.day, row.part), &row.outcome) {
+            (None, _) => Status::Missing,
+            (Some(e), Ok(answer)) if answers::matches(e, answer) => Status::Pass,
+            (Some(e), _) => Status::Fail(e.clone()),
+        };
+        row.status = Some(status);
+    }
+}
+
+/// Whether everything that ran was correct, Missing expectations are not a failure but a part
+/// which failed to produce an answer always is
+pub fn success(rows: &[Row]) -> bool {
+    rows.iter()
+        .all(|row| row.outcome.is_ok() && !matches!(row.status, Some(Status::Fail(_))))
+}
+
+fn duration(time: Duration) -> String {
+    let secs = time.as_secs_f64();
+    if secs >= 1.0 {
+        format!("{secs:.3}s")
+    } else {
+        format!("{:.3}ms", secs * 1000.0)
+    }
+}
+
+/// Table with a line for each part, multi-line answers follow on the lines below
+pub fn table(rows: &[Row]) -> String {
+    let answers: Vec<String> = rows
+        .iter()
+        .map(|row| match &row.outcome {
+            Ok(Answer::Picture(_)) => "(picture)".to_string(),
+            Ok(answer) => answer.to_string(),
+            Err(e) => format!("FAILED: {e}"),
+        })
+        .collect();
+    let width = answers.iter().map(|a| a.chars().count()).max().unwrap_or(0);
+    let width = width.max("Answer".len());
+
+    let checked = rows.iter().any(|row| row.status.is_some());
+    let mut s = format!("Day Part {:width$} {:>10}", "Answer", "Time");
+    if checked {
+        s += " Status";
+    }
+    s.push('\n');
+    for (row, answer) in rows.iter().zip(answers) {
+        s += &format!(
+            "{:>3} {:>4} {answer:width$} {:>10}",
+            row.day,
+            row.part,
+            duration(row.time)
+        );
+        match &row.status {
+            Some(Status::Fail(expected)) if !expected.to_string().contains('\n') => {
+                s += &format!(" FAIL (expected {expected})");
+            }
+            Some(status) => {
+                s.push(' ');
+                s += status.label();
+            }
+            None => {}
+        }
+        s.push('\n');
+        if let Ok(Answer::Picture(picture)) = &row.outcome {
+            for line in picture.lines() {
+                s += &format!("         {line}\n");
+            }
+        }
+    }
+    let total: Duration = rows.iter().map(|row| row.time).sum();
+    s += &format!(
+        "{:>width$} {:>10}\n",
+        "Total",
+        duration(total),
+        width = width + 9
+    );
+    s
+}
+
+fn quote(s: &str) -> String {
+    let mut q = String::from('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => q.push_str("\\\""),
+            '\\' => q.push_str("\\\\"),
+            '\n' => q.push_str("\\n"),
+            '\r' => q.push_str("\\r"),
+            '\t' => q.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => q += &format!("\\u{:04x}", ch as u32),
+            ch => q.push(ch),
+        }
+    }
+    q.push('"');
+    q
+}
+
+/// JSON array with an object for each part
+pub fn json(rows: &[Row]) -> String {
+    let mut v = Vec::new();
+    for row in rows {
+        let outcome = match &row.outcome {
+            Ok(Answer::Number(n)) => format!("\"answer\": {n}"),
+            Ok(answer) => format!("\"answer\": {}", quote(&answer.to_string())),
+            Err(e) => format!("\"error\": {}", quote(e)),
+        };
+        let status = match &row.status {
+            Some(status) => format!(", \"status\": \"{}\"", status.label()),
+            None => String::new(),
+        };
+        v.push(format!(
+            "  {{\"day\": {}, \"part\": \"{}\", {outcome}, \"seconds\": {}{status}}}",
+            row.day,
+            row.part,
+            row.time.as_secs_f64()
+        ));
+    }
+    format!("[\n{}\n]\n", v.join(",\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::{check, run, select, success, Status};
+    use jungle::answers::Answers;
+    use jungle::{Answer, Contents, Day, Input, Part, Registry};
+
+    fn nothing(_ctxt: &Contents) -> Answer {
+        0.into()
+    }
+
+    #[test]
+    fn selections() {
+        let mut registry = Registry::new();
+        for day in [1, 2, 3, 7, 9] {
+            registry.add(Day::new(day, nothing, nothing));
+        }
+        let which = |w: &[&str]| {
+            let w: Vec<String> = w.iter().map(|s| s.to_string()).collect();
+            select(&registry, &w)
+        };
+        assert_eq!(which(&["4b"]), Err("4b not available yet".to_string()));
+        assert_eq!(which(&["2b"]), Ok(vec![(2, Part::B)]));
+        assert_eq!(which(&["7*", "7a"]), Ok(vec![(7, Part::A), (7, Part::B)]));
+        assert_eq!(which(&["all"]).unwrap().len(), 10);
+        assert_eq!(
+            which(&["3-8a", "1"]),
+            Ok(vec![(1, Part::A), (1, Part::B), (3, Part::A), (7, Part::A)])
+        );
+        assert!(which(&["9-3"]).is_err());
+        assert!(which(&["x"]).is_err());
+    }
+
+    fn count(ctxt: &Contents) -> Answer {
+        ctxt.lines().count().into()
+    }
+
+    #[test]
+    fn checks() {
+        let mut registry = Registry::new();
+        registry.add(Day::new(4, count, nothing));
+        let input = Input::file(file!());
+        let parts = [(4, Part::A), (4, Part::B)];
+        let mut rows = run(&registry, &input, &parts);
+        let lines = include_str!("run.rs").lines().count();
+        assert_eq!(rows[0].outcome, Ok(lines.into()));
+        assert!(success(&rows));
+
+        let manifest = format!("[{}]\n4a = {lines}\n", file!());
+        let expected = Answers::parse(&Contents { text: manifest }).unwrap();
+        check(&mut rows, &expected, &input.set());
+        assert_eq!(rows[0].status, Some(Status::Pass));
+        assert_eq!(rows[1].status, Some(Status::Missing));
+        assert!(success(&rows));
+
+        let manifest = format!("['{}']\n4b = 1\n", file!());
+        let expected = Answers::parse(&Contents { text: manifest }).unwrap();
+        check(&mut rows, &expected, &input.set());
+        assert_eq!(rows[1].status, Some(Status::Fail(Answer::Number(1))));
+        assert!(!success(&rows));
+    }
+
+    fn broken(_ctxt: &Contents) -> Answer {
+        panic!("Inconceivable!")
+    }
+
+    #[test]
+    fn panics() {
+        let mut registry = Registry::new();
+        registry.add(Day::new(5, nothing, broken));
+        let input = Input::file(file!());
+        let mut rows = run(&registry, &input, &[(5, Part::A), (5, Part::B)]);
+        assert!(rows[1].outcome.is_err());
+        assert!(!success(&rows));
+
+        let manifest = format!("['{}']\n5a = 0\n", file!());
+        let expected = Answers::parse(&Contents { text: manifest }).unwrap();
+        check(&mut rows, &expected, &input.set());
+        assert_eq!(rows[0].status, Some(Status::Pass));
+        assert_eq!(rows[1].status, Some(Status::Missing));
+        assert!(!success(&rows));
+    }
+}